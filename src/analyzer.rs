@@ -1,14 +1,29 @@
-use crate::cli::{Cli, TokenizerType};
+use crate::budget::{fit_to_budget, Budgeted};
+use crate::cli::{Cli, DetectionMode, SortOrder, TokenizerType};
 use crate::file_picker::FilePicker;
-use crate::output::{display_token_counts, generate_output, handle_output, FileEntry};
-use crate::source_detection;
+use crate::git::{self, ChangeSet};
+use crate::lockfile;
+use crate::output::{
+    apply_template, display_token_counts, generate_chunks, generate_output, handle_chunks,
+    handle_output, number_entries, skipped_summary, template_tokens, FileEntry, LineOrigin,
+    OutputContext, SkipReason, SkippedFile,
+};
+use crate::redact::{self, Redactor};
+use crate::skeleton;
+use crate::slice::{self, Selection};
+use crate::source_detection::{self, CustomTypes};
+use crate::strip;
 use crate::tokenizer::TokenCounter;
-use anyhow::Result;
+use anyhow::{bail, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use ignore::WalkBuilder;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
 pub fn process_directory(args: &Cli) -> Result<()> {
     // Configure thread pool if specified
@@ -42,61 +57,354 @@ pub fn process_directory(args: &Cli) -> Result<()> {
         .git_ignore(!args.no_ignore)
         .ignore(!args.no_ignore);
 
-    // Add custom ignore/include patterns
+    // Add custom ignore patterns
     if let Some(ref excludes) = args.exclude {
         for pattern in excludes {
             builder.add_ignore(pattern);
         }
     }
-    if let Some(ref includes) = args.include {
-        for pattern in includes {
-            builder.add_custom_ignore_filename(pattern);
-        }
+
+    // Type filters run in the walker, so unwanted types are never visited
+    let type_filter = source_detection::TypeFilter::new(
+        args.types.as_deref().unwrap_or_default(),
+        args.type_not.as_deref().unwrap_or_default(),
+        &args.custom_types,
+    )?;
+    if !type_filter.is_empty() {
+        builder.filter_entry(move |entry| {
+            !entry.file_type().is_some_and(|ft| ft.is_file()) || type_filter.matches(entry.path())
+        });
+    }
+
+    // Includes replace source detection, so they can also admit unknown file types
+    let include_set = args.include.as_deref().map(build_include_set).transpose()?;
+
+    // Restrict the walk to files touched in git, if requested
+    let change_set = change_set(args)?;
+    if args.diff && change_set.is_none() {
+        bail!("--diff requires --changed-since, --staged or --uncommitted");
     }
+    let changed_files = change_set
+        .as_ref()
+        .map(|changes| git::changed_files(&args.path, changes))
+        .transpose()?;
 
-    // Collect all valid files
-    let entries = if args.interactive {
-        let mut picker = FilePicker::new(args.path.clone(), max_size, args.hidden, args.no_ignore);
+    // Content sniffing either replaces name-based detection or double checks it
+    let sniff = args.detect != DetectionMode::Extension;
+
+    // Collect all valid files, keeping track of what was skipped and why
+    let mut visited = HashSet::new();
+    let results: Vec<std::result::Result<FileEntry, SkippedFile>> = if args.interactive {
+        // Oversized files can be picked too when they'll be truncated
+        let picker_max_size = if args.truncate { u64::MAX } else { max_size };
+        let mut picker = FilePicker::new(
+            args.path.clone(),
+            picker_max_size,
+            args.hidden,
+            args.no_ignore,
+        );
         let selected_paths = picker.run()?;
 
         // Process selected files
         selected_paths
             .into_iter()
-            .filter_map(|path| {
-                let entry = ignore::WalkBuilder::new(&path)
-                    .build()
-                    .next()
-                    .and_then(|r| r.ok());
-                entry.and_then(|e| process_file(&e, &args.path).ok())
+            .map(|path| {
+                process_file(&path, &args.path, sniff, &args.custom_types).map_err(|e| {
+                    SkippedFile {
+                        path: relative_path(&path, &args.path),
+                        reason: skip_reason(&e),
+                    }
+                })
+            })
+            .collect()
+    } else if !args.file_specs.is_empty() {
+        // File specs name their files, so there's nothing to walk or filter by
+        // name, but lockfiles and generated files are handled as in a walk
+        let mut seen = HashSet::new();
+        args.file_specs
+            .iter()
+            .map(|spec| &spec.path)
+            .filter(|path| seen.insert(*path))
+            .map(|path| {
+                let skip = |reason| SkippedFile {
+                    path: path.clone(),
+                    reason,
+                };
+                let full_path = args.path.join(path);
+                let file = process_file(&full_path, &args.path, sniff, &args.custom_types)
+                    .map_err(|e| skip(skip_reason(&e)))?;
+                screen_file(file, &full_path, args).map_err(skip)
             })
-            .collect::<Vec<FileEntry>>()
+            .collect()
     } else {
-        builder
+        let mut walk_errors = Vec::new();
+        let files: Vec<ignore::DirEntry> = builder
             .build()
-            .par_bridge()
-            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.map_err(|e| walk_errors.push(e)).ok())
             .filter(|entry| {
+                if args.report_skipped {
+                    visited.insert(entry.path().to_path_buf());
+                }
                 entry.file_type().map(|ft| ft.is_file()).unwrap_or(false)
-                    && source_detection::is_source_file(entry.path())
-                    && entry
-                        .metadata()
-                        .map(|m| m.len() <= max_size)
-                        .unwrap_or(false)
             })
-            .filter_map(|entry| process_file(&entry, &args.path).ok())
-            .collect()
+            .collect();
+
+        // Unreadable directories and the like still get reported, as read errors
+        let unreadable: Vec<SkippedFile> = walk_errors
+            .iter()
+            .filter_map(|e| match error_path(e) {
+                Some(path) => {
+                    visited.insert(path.to_path_buf());
+                    Some(SkippedFile {
+                        path: relative_path(path, &args.path),
+                        reason: SkipReason::ReadError,
+                    })
+                }
+                None => {
+                    eprintln!("Warning: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        let mut results: Vec<_> = files
+            .par_iter()
+            .map(|entry| {
+                let skip = |reason| SkippedFile {
+                    path: relative_path(entry.path(), &args.path),
+                    reason,
+                };
+
+                match &include_set {
+                    Some(set) if !is_included(set, entry.path(), &args.path) => {
+                        return Err(skip(SkipReason::Ignored))
+                    }
+                    None if args.detect != DetectionMode::Content
+                        && !source_detection::is_source_file(entry.path(), &args.custom_types) =>
+                    {
+                        return Err(skip(SkipReason::UnknownType))
+                    }
+                    _ => {}
+                }
+                let is_changed = changed_files.as_ref().is_none_or(|changed| {
+                    changed.contains(&relative_path(entry.path(), &args.path))
+                });
+                if !is_changed {
+                    return Err(skip(SkipReason::Ignored));
+                }
+                // Lockfiles are measured once summarized, they shrink a lot
+                let summarized = lockfile::is_lockfile(entry.path()) && !args.full_lockfiles;
+                match entry.metadata() {
+                    Ok(metadata) if metadata.len() > max_size && !args.truncate && !summarized => {
+                        return Err(skip(SkipReason::TooLarge))
+                    }
+                    Err(_) => return Err(skip(SkipReason::ReadError)),
+                    Ok(_) => {}
+                }
+
+                let file = process_file(entry.path(), &args.path, sniff, &args.custom_types)
+                    .map_err(|e| skip(skip_reason(&e)))?;
+                let file = screen_file(file, entry.path(), args).map_err(skip)?;
+                // A lockfile that didn't parse is kept whole, so the limit applies after all
+                if summarized
+                    && file.origin != LineOrigin::Synthetic
+                    && file.size > max_size
+                    && !args.truncate
+                {
+                    return Err(skip(SkipReason::TooLarge));
+                }
+                Ok(file)
+            })
+            .collect();
+        results.extend(unreadable.into_iter().map(Err));
+        results
     };
+    let (mut entries, mut skipped): (Vec<FileEntry>, Vec<SkippedFile>) = (Vec::new(), Vec::new());
+    for result in results {
+        match result {
+            Ok(entry) => entries.push(entry),
+            Err(file) => skipped.push(file),
+        }
+    }
+
+    // Ignored files are only worth listing in full, which needs a second walk.
+    // File specs name their files outright, so nothing else counts as ignored.
+    if args.report_skipped && !args.interactive && args.file_specs.is_empty() {
+        skipped.extend(find_ignored(&args.path, max_depth, &visited));
+    } else {
+        skipped.retain(|file| file.reason != SkipReason::Ignored);
+    }
+    skipped.sort_by(|a, b| a.path.cmp(&b.path));
     pb.finish();
 
+    if !skipped.is_empty() {
+        if args.report_skipped {
+            eprintln!("{}", skipped_summary(&skipped));
+        } else {
+            eprintln!(
+                "{}; use --report-skipped to list them",
+                skipped_summary(&skipped)
+            );
+        }
+    }
+
+    if let (true, Some(changes)) = (args.diff, &change_set) {
+        entries.par_iter_mut().try_for_each(|entry| -> Result<()> {
+            let diff = git::file_diff(&args.path, changes, &entry.path)?;
+            entry.diff = (!diff.is_empty()).then_some(diff);
+            Ok(())
+        })?;
+    }
+
+    // Redact while the content is whole, so the report's line numbers match the files
+    if !args.no_redact {
+        let redactor = Redactor::new(&args.redaction)?;
+        let findings = redact::redact_entries(&mut entries, &redactor);
+        if !findings.is_empty() {
+            eprint!("{}", redact::redaction_report(&findings));
+        }
+    }
+
+    // Slice once redaction is done, which keeps lines where they were
+    if !args.file_specs.is_empty() {
+        entries.par_iter_mut().try_for_each(|entry| -> Result<()> {
+            let selections: Vec<&Selection> = args
+                .file_specs
+                .iter()
+                .filter(|spec| spec.path == entry.path)
+                .map(|spec| &spec.selection)
+                .collect();
+            if let Some(edits) =
+                slice::slice(&entry.path, entry.language, &entry.content, &selections)?
+            {
+                entry.splice(&edits);
+            }
+            Ok(())
+        })?;
+    }
+
+    if args.skeleton {
+        entries.par_iter_mut().for_each(|entry| {
+            if let Some(elisions) = skeleton::skeleton(entry.language, &entry.content) {
+                entry.splice(&elisions);
+            }
+        });
+    }
+
+    let needs_counter = !args.no_tokens
+        || args.max_tokens.is_some()
+        || args.chunk_tokens.is_some()
+        || args.sort == SortOrder::Tokens
+        || args.truncate
+        || args.max_file_tokens.is_some();
+    let counter = if !needs_counter {
+        None
+    } else {
+        Some(create_token_counter(args)?)
+    };
+
+    // Strip before sorting and budgeting, so both see the tokens actually sent
+    let strip_savings = if args.strip.is_empty() {
+        Vec::new()
+    } else {
+        let counter = counter.as_ref().filter(|_| !args.no_tokens);
+        strip::strip_entries(&mut entries, &args.strip, counter)?
+    };
+
+    // Truncate before numbering lines, so the tail keeps its real line numbers
+    let truncated = match &counter {
+        Some(counter) if args.truncate || args.max_file_tokens.is_some() => {
+            slice::truncate_entries(&mut entries, args, counter)?
+        }
+        _ => Vec::new(),
+    };
+
+    // Structured formats keep content verbatim; the gutter counts toward the budget
+    let gutter_tokens = if args.line_numbers && !matches!(output_format, "json" | "jsonl") {
+        let counter = counter.as_ref().filter(|_| !args.no_tokens);
+        number_entries(&mut entries, counter)?
+    } else {
+        None
+    };
+
+    // Parallel collection yields files in arbitrary order, so settle on a stable one
+    if !args.interactive {
+        sort_entries(&mut entries, &args.sort, counter.as_ref(), &args.path)?;
+    }
+
+    // Wrapping JSON in prose would leave it unparseable
+    let machine_readable = matches!(output_format, "json" | "jsonl");
+    let template = match args.prompt_template.as_deref() {
+        Some(_) if machine_readable => {
+            eprintln!("Prompt template skipped for {} output", output_format);
+            None
+        }
+        template => template,
+    };
+
+    // Trim the selection to the token budget, if any
+    let reported: &[SkippedFile] = if args.report_skipped { &skipped } else { &[] };
+    let Budgeted { entries, omitted } = match (args.max_tokens, &counter) {
+        (Some(max_tokens), Some(counter)) => {
+            let total = entries.len();
+            let context = OutputContext {
+                skipped: reported,
+                truncated: &truncated,
+                ..Default::default()
+            };
+            // The template wraps the output, so it comes out of the same budget
+            let budget = max_tokens.saturating_sub(template_tokens(template, counter)?);
+            let budgeted = fit_to_budget(entries, &context, counter, budget, args)?;
+            if !budgeted.omitted.is_empty() {
+                eprintln!(
+                    "Token budget: omitted {} of {} files to fit within {} tokens",
+                    budgeted.omitted.len(),
+                    total,
+                    max_tokens
+                );
+            }
+            budgeted
+        }
+        _ => Budgeted {
+            entries,
+            omitted: Vec::new(),
+        },
+    };
+
+    // Count tokens up front so structured formats can embed them
+    let token_count = match &counter {
+        Some(counter) if !args.no_tokens => Some(counter.count_files(&entries)?),
+        _ => None,
+    };
+
     // Generate output
-    let output = generate_output(&entries, output_format)?;
+    let context = OutputContext {
+        token_count: token_count.as_ref(),
+        omitted: &omitted,
+        skipped: reported,
+        truncated: &truncated,
+    };
 
-    // Handle output (print/copy/save)
-    handle_output(output, args)?;
+    // Handle output (print/copy/save), one part at a time when chunking
+    match (args.chunk_tokens, &counter) {
+        (Some(chunk_tokens), Some(counter)) => {
+            let budget = chunk_tokens.saturating_sub(template_tokens(template, counter)?);
+            let mut parts = generate_chunks(&entries, output_format, &context, counter, budget)?;
+            apply_template(template, &mut parts);
+            handle_chunks(parts, args)?;
+        }
+        _ => {
+            let mut output = generate_output(&entries, output_format, &context)?;
+            apply_template(template, std::slice::from_mut(&mut output));
+            handle_output(output, args)?;
+        }
+    }
 
-    if !args.no_tokens {
-        let counter = create_token_counter(args)?;
-        display_token_counts(counter, &entries)?;
+    // Structured output already carries the token summary, keep stdout parseable
+    if let Some(token_count) = token_count {
+        if !(machine_readable && args.print) {
+            display_token_counts(&token_count, &strip_savings, gutter_tokens)?;
+        }
     }
 
     Ok(())
@@ -123,13 +431,326 @@ pub fn create_token_counter(args: &Cli) -> Result<TokenCounter> {
     }
 }
 
-fn process_file(entry: &ignore::DirEntry, base_path: &Path) -> Result<FileEntry> {
-    let relative_path = entry.path().strip_prefix(base_path)?;
-    let content = fs::read_to_string(entry.path())?;
+/// Sorts entries by the requested key, falling back to path so ties stay stable
+fn sort_entries(
+    entries: &mut [FileEntry],
+    order: &SortOrder,
+    counter: Option<&TokenCounter>,
+    base_path: &Path,
+) -> Result<()> {
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    match order {
+        SortOrder::Path => {}
+        SortOrder::Size => entries.sort_by_key(|e| e.size),
+        SortOrder::Tokens => {
+            let counter = counter.expect("token counter should exist when sorting by tokens");
+            let mut keyed = entries
+                .par_iter()
+                .map(|e| Ok((counter.count_tokens(&e.content)?, e.clone())))
+                .collect::<Result<Vec<_>>>()?;
+            keyed.sort_by_key(|(tokens, _)| *tokens);
+            for (slot, (_, entry)) in entries.iter_mut().zip(keyed) {
+                *slot = entry;
+            }
+        }
+        SortOrder::Mtime => entries.sort_by_cached_key(|e| {
+            let modified = fs::metadata(base_path.join(&e.path)).and_then(|m| m.modified());
+            std::cmp::Reverse(modified.ok())
+        }),
+        SortOrder::Important => entries.sort_by_cached_key(|e| {
+            (
+                source_detection::importance_rank(&e.path),
+                e.path.components().count(),
+            )
+        }),
+    }
+
+    Ok(())
+}
+
+fn change_set(args: &Cli) -> Result<Option<ChangeSet>> {
+    Ok(if let Some(rev) = &args.changed_since {
+        Some(ChangeSet::since(&args.path, rev)?)
+    } else if args.staged {
+        Some(ChangeSet::Staged)
+    } else if args.uncommitted {
+        Some(ChangeSet::Uncommitted)
+    } else {
+        None
+    })
+}
+
+fn build_include_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Matches include globs against the path relative to the root, or just the file name
+fn is_included(set: &GlobSet, path: &Path, base_path: &Path) -> bool {
+    let relative_path = path.strip_prefix(base_path).unwrap_or(path);
+    set.is_match(relative_path) || path.file_name().is_some_and(|name| set.is_match(name))
+}
+
+fn process_file(
+    path: &Path,
+    base_path: &Path,
+    sniff: bool,
+    custom_types: &CustomTypes,
+) -> Result<FileEntry> {
+    let relative_path = path.strip_prefix(base_path)?;
+    let content = if sniff {
+        source_detection::decode_text(fs::read(path)?)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "binary content"))?
+    } else {
+        fs::read_to_string(path)?
+    };
 
     Ok(FileEntry {
         path: relative_path.to_path_buf(),
+        language: source_detection::detect_language_in(relative_path, &content, custom_types),
         content,
-        size: entry.metadata()?.len(),
+        size: fs::metadata(path)?.len(),
+        diff: None,
+        origin: LineOrigin::Unchanged,
     })
 }
+
+/// Summarizes lockfiles and holds back generated files. `path` is the file
+/// on disk, which a lockfile's manifest is found next to.
+fn screen_file(
+    mut file: FileEntry,
+    path: &Path,
+    args: &Cli,
+) -> std::result::Result<FileEntry, SkipReason> {
+    // Lockfiles are generated too, but get summarized rather than skipped
+    if lockfile::is_lockfile(&file.path) {
+        if !args.full_lockfiles {
+            if let Some(summary) = lockfile::summarize(path, &file.content) {
+                file.content = summary;
+                file.origin = LineOrigin::Synthetic;
+            }
+        }
+        return Ok(file);
+    }
+    let is_generated = source_detection::classify_provenance(&file.path, &file.content).is_some();
+    if is_generated && !args.include_generated {
+        return Err(SkipReason::Generated);
+    }
+    Ok(file)
+}
+
+/// The path a walk error is about, if it names one
+fn error_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            error_path(err)
+        }
+        _ => None,
+    }
+}
+
+fn relative_path(path: &Path, base_path: &Path) -> PathBuf {
+    path.strip_prefix(base_path).unwrap_or(path).to_path_buf()
+}
+
+/// Content that isn't valid UTF-8 is treated as binary, anything else as a read failure
+fn skip_reason(error: &anyhow::Error) -> SkipReason {
+    match error.downcast_ref::<io::Error>() {
+        Some(e) if e.kind() == io::ErrorKind::InvalidData => SkipReason::Binary,
+        _ => SkipReason::ReadError,
+    }
+}
+
+/// Walks again without any filters to find what the first walk left out.
+/// Ignored directories are reported once rather than file by file.
+fn find_ignored(root: &Path, max_depth: usize, visited: &HashSet<PathBuf>) -> Vec<SkippedFile> {
+    let mut ignored = Vec::new();
+    let mut walker = WalkDir::new(root).max_depth(max_depth).into_iter();
+
+    while let Some(Ok(entry)) = walker.next() {
+        if visited.contains(entry.path()) {
+            continue;
+        }
+        let is_dir = entry.file_type().is_dir();
+        if is_dir {
+            walker.skip_current_dir();
+        }
+        if entry.file_name() == ".git" {
+            continue;
+        }
+
+        let mut path = relative_path(entry.path(), root);
+        if is_dir {
+            // A trailing separator marks the whole directory as ignored
+            path.push("");
+        }
+        ignored.push(SkippedFile {
+            path,
+            reason: SkipReason::Ignored,
+        });
+    }
+
+    ignored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_include_patterns() {
+        let patterns = vec![
+            "*.rs".to_string(),
+            "*.lock".to_string(),
+            "docs/**".to_string(),
+        ];
+        let set = build_include_set(&patterns).unwrap();
+        let base = PathBuf::from("project");
+
+        let test_cases = vec![
+            ("project/src/main.rs", true),
+            ("project/Cargo.lock", true),
+            ("project/docs/guide/intro.txt", true),
+            ("project/src/main.go", false),
+            ("project/src/docs/notes.txt", false),
+        ];
+
+        for (file, expected) in test_cases {
+            let path = PathBuf::from(file);
+            assert_eq!(
+                is_included(&set, &path, &base),
+                expected,
+                "Failed for {}",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_include_matches_file_name() {
+        let set = build_include_set(&["Makefile".to_string()]).unwrap();
+        let path = PathBuf::from("project/build/Makefile");
+        assert!(is_included(&set, &path, Path::new("project")));
+    }
+
+    fn entry(path: &str, size: u64) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from(path),
+            content: "x".repeat(size as usize),
+            size,
+            diff: None,
+            language: None,
+            origin: LineOrigin::Unchanged,
+        }
+    }
+
+    fn sorted_paths(mut entries: Vec<FileEntry>, order: SortOrder) -> Vec<String> {
+        let counter = TokenCounter::new("gpt-4o").unwrap();
+        sort_entries(&mut entries, &order, Some(&counter), Path::new(".")).unwrap();
+        entries
+            .iter()
+            .map(|e| e.path.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_entries() {
+        let entries = vec![
+            entry("src/output.rs", 30),
+            entry("README.md", 20),
+            entry("src/main.rs", 10),
+            entry("Cargo.toml", 30),
+        ];
+
+        assert_eq!(
+            sorted_paths(entries.clone(), SortOrder::Path),
+            vec!["Cargo.toml", "README.md", "src/main.rs", "src/output.rs"]
+        );
+        assert_eq!(
+            sorted_paths(entries.clone(), SortOrder::Size),
+            vec!["src/main.rs", "README.md", "Cargo.toml", "src/output.rs"]
+        );
+        assert_eq!(
+            sorted_paths(entries.clone(), SortOrder::Important),
+            vec!["README.md", "Cargo.toml", "src/main.rs", "src/output.rs"]
+        );
+        assert_eq!(sorted_paths(entries, SortOrder::Tokens)[0], "src/main.rs");
+    }
+
+    #[test]
+    fn test_skip_reasons_and_ignored_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("blob.rs"), [0xff, 0xfe, 0x00, 0x9f]).unwrap();
+        fs::create_dir(root.join("target")).unwrap();
+        fs::write(root.join("target/out.rs"), "").unwrap();
+
+        let error =
+            process_file(&root.join("blob.rs"), root, false, &CustomTypes::default()).unwrap_err();
+        assert_eq!(skip_reason(&error), SkipReason::Binary);
+        let error = process_file(
+            &root.join("missing.rs"),
+            root,
+            false,
+            &CustomTypes::default(),
+        )
+        .unwrap_err();
+        assert_eq!(skip_reason(&error), SkipReason::ReadError);
+
+        let visited: HashSet<PathBuf> = [root.to_path_buf(), root.join("main.rs")]
+            .into_iter()
+            .collect();
+        let mut ignored: Vec<String> = find_ignored(root, 10, &visited)
+            .iter()
+            .map(|file| file.path.display().to_string())
+            .collect();
+        ignored.sort();
+        assert_eq!(ignored, vec!["blob.rs", "target/"]);
+    }
+
+    #[test]
+    fn test_process_file_sniffs_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        // Valid UTF-8, but NUL bytes give away a binary MPEG-TS segment
+        fs::write(root.join("segment.ts"), b"G\x40\x00\x10\x00\x00").unwrap();
+        fs::write(root.join("notes.utf16"), b"\xff\xfeh\x00i\x00").unwrap();
+
+        assert!(process_file(
+            &root.join("segment.ts"),
+            root,
+            false,
+            &CustomTypes::default()
+        )
+        .is_ok());
+        let error = process_file(
+            &root.join("segment.ts"),
+            root,
+            true,
+            &CustomTypes::default(),
+        )
+        .unwrap_err();
+        assert_eq!(skip_reason(&error), SkipReason::Binary);
+
+        let entry = process_file(
+            &root.join("notes.utf16"),
+            root,
+            true,
+            &CustomTypes::default(),
+        )
+        .unwrap();
+        assert_eq!(entry.content, "hi");
+    }
+
+    #[test]
+    fn test_invalid_include_pattern() {
+        assert!(build_include_set(&["src/[".to_string()]).is_err());
+    }
+}
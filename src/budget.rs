@@ -0,0 +1,370 @@
+use crate::cli::{BudgetPriority, Cli};
+use crate::git;
+use crate::output::{generate_output, FileEntry, OutputContext};
+use crate::tokenizer::{TokenCount, TokenCounter};
+use anyhow::{bail, Result};
+use globset::{Glob, GlobSetBuilder};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+pub struct Budgeted {
+    pub entries: Vec<FileEntry>,
+    /// Files dropped to fit the budget, listed in the tree but not the contents
+    pub omitted: Vec<PathBuf>,
+}
+
+/// Drops whole files in priority order until the rendered output fits within
+/// `max_tokens`. Files are never truncated to fit, a file either fits or goes.
+/// The skipped and truncated lists in `context` are rendered along with them.
+pub fn fit_to_budget(
+    entries: Vec<FileEntry>,
+    context: &OutputContext,
+    counter: &TokenCounter,
+    max_tokens: usize,
+    args: &Cli,
+) -> Result<Budgeted> {
+    let format = args
+        .output
+        .as_deref()
+        .expect("output format should be set from config");
+
+    // The tree lists every file regardless, so there is nothing to trim
+    if format == "tree" {
+        return Ok(Budgeted {
+            entries,
+            omitted: Vec::new(),
+        });
+    }
+
+    let content_tokens = entries
+        .iter()
+        .map(|entry| counter.count_tokens(&entry.content))
+        .collect::<Result<Vec<_>>>()?;
+    let order = priority_order(&entries, &content_tokens, args)?;
+    let measure = Measure {
+        counter,
+        format,
+        context,
+        with_tokens: !args.no_tokens,
+    };
+
+    fit_in_order(entries, &content_tokens, &order, max_tokens, &measure)
+}
+
+/// Everything besides the selection itself that goes into the rendered output
+struct Measure<'a> {
+    counter: &'a TokenCounter,
+    format: &'a str,
+    context: &'a OutputContext<'a>,
+    /// Whether token counts get embedded, as the structured formats do
+    with_tokens: bool,
+}
+
+/// Returns entry indices, most important first
+fn priority_order(
+    entries: &[FileEntry],
+    content_tokens: &[usize],
+    args: &Cli,
+) -> Result<Vec<usize>> {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    let by_path = |a: &usize, b: &usize| entries[*a].path.cmp(&entries[*b].path);
+
+    match args.budget_priority {
+        BudgetPriority::Smallest => order.sort_by(|a, b| {
+            content_tokens[*a]
+                .cmp(&content_tokens[*b])
+                .then(by_path(a, b))
+        }),
+        BudgetPriority::Depth => order.sort_by(|a, b| {
+            let depth = |i: &usize| entries[*i].path.components().count();
+            depth(a).cmp(&depth(b)).then(by_path(a, b))
+        }),
+        BudgetPriority::Recent => {
+            let times = modification_times(entries, &args.path);
+            order.sort_by(|a, b| times[*b].cmp(&times[*a]).then(by_path(a, b)))
+        }
+        BudgetPriority::List => {
+            let Some(patterns) = args.priority.as_deref() else {
+                bail!("--budget-priority list requires --priority patterns");
+            };
+            let ranks = pattern_ranks(entries, patterns)?;
+            order.sort_by(|a, b| ranks[*a].cmp(&ranks[*b]).then(by_path(a, b)))
+        }
+    }
+
+    Ok(order)
+}
+
+/// Ranks each entry by the first priority pattern it matches, unmatched entries last
+fn pattern_ranks(entries: &[FileEntry], patterns: &[String]) -> Result<Vec<usize>> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    let set = builder.build()?;
+
+    Ok(entries
+        .iter()
+        .map(|entry| {
+            set.matches(&entry.path)
+                .into_iter()
+                .min()
+                .unwrap_or(patterns.len())
+        })
+        .collect())
+}
+
+/// Last commit time per entry, falling back to the filesystem for untracked files
+fn modification_times(entries: &[FileEntry], root: &Path) -> Vec<i64> {
+    let commit_times = git::last_commit_times(root).unwrap_or_default();
+
+    entries
+        .iter()
+        .map(|entry| {
+            commit_times.get(&entry.path).copied().unwrap_or_else(|| {
+                fs::metadata(root.join(&entry.path))
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map_or(0, |duration| duration.as_secs() as i64)
+            })
+        })
+        .collect()
+}
+
+fn fit_in_order(
+    entries: Vec<FileEntry>,
+    content_tokens: &[usize],
+    order: &[usize],
+    max_tokens: usize,
+    measure: &Measure,
+) -> Result<Budgeted> {
+    let render = |kept: &HashSet<usize>| -> Result<usize> {
+        let (kept_entries, omitted) = split(&entries, kept);
+        let token_count = measure.with_tokens.then(|| {
+            let breakdown: Vec<(PathBuf, usize)> = (0..entries.len())
+                .filter(|i| kept.contains(i))
+                .map(|i| (entries[i].path.clone(), content_tokens[i]))
+                .collect();
+            TokenCount {
+                total_tokens: breakdown.iter().map(|(_, tokens)| tokens).sum(),
+                breakdown,
+            }
+        });
+        let context = OutputContext {
+            token_count: token_count.as_ref(),
+            omitted: &omitted,
+            ..*measure.context
+        };
+        let output = generate_output(&kept_entries, measure.format, &context)?;
+        measure.counter.count_tokens(&output)
+    };
+
+    let all: HashSet<usize> = (0..entries.len()).collect();
+    let full_tokens = render(&all)?;
+    if full_tokens <= max_tokens {
+        return Ok(Budgeted {
+            entries,
+            omitted: Vec::new(),
+        });
+    }
+
+    // Spread the headers, fences and tree markers evenly as a per-file overhead
+    let base_tokens = render(&HashSet::new())?;
+    let total_content: usize = content_tokens.iter().sum();
+    let overhead = full_tokens
+        .saturating_sub(base_tokens + total_content)
+        .div_ceil(entries.len().max(1));
+    let cost = |i: usize| content_tokens[i] + overhead;
+
+    // Greedily keep files in priority order, skipping any that no longer fit
+    let mut kept = HashSet::new();
+    let mut remaining = max_tokens.saturating_sub(base_tokens);
+    for &i in order {
+        if cost(i) <= remaining {
+            remaining -= cost(i);
+            kept.insert(i);
+        }
+    }
+
+    // The estimate can undershoot, so verify against the real render
+    loop {
+        let tokens = render(&kept)?;
+        if tokens <= max_tokens || kept.is_empty() {
+            break;
+        }
+
+        let mut excess = tokens - max_tokens;
+        for &i in order.iter().rev() {
+            if excess == 0 {
+                break;
+            }
+            if kept.remove(&i) {
+                excess = excess.saturating_sub(cost(i));
+            }
+        }
+    }
+
+    let (kept_entries, omitted) = split(&entries, &kept);
+    Ok(Budgeted {
+        entries: kept_entries,
+        omitted,
+    })
+}
+
+/// Splits entries into kept ones and the paths of the rest, keeping the original order
+fn split(entries: &[FileEntry], kept: &HashSet<usize>) -> (Vec<FileEntry>, Vec<PathBuf>) {
+    let mut kept_entries = Vec::new();
+    let mut omitted = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if kept.contains(&i) {
+            kept_entries.push(entry.clone());
+        } else {
+            omitted.push(entry.path.clone());
+        }
+    }
+    (kept_entries, omitted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{LineOrigin, SkipReason, SkippedFile};
+
+    fn entry(path: &str, content: &str) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from(path),
+            content: content.to_string(),
+            size: content.len() as u64,
+            diff: None,
+            language: None,
+            origin: LineOrigin::Unchanged,
+        }
+    }
+
+    fn measure<'a>(counter: &'a TokenCounter, context: &'a OutputContext) -> Measure<'a> {
+        Measure {
+            counter,
+            format: "both",
+            context,
+            with_tokens: false,
+        }
+    }
+
+    fn create_test_entries() -> Vec<FileEntry> {
+        vec![
+            entry(
+                "src/main.rs",
+                &"fn main() { println!(\"hello\"); }\n".repeat(20),
+            ),
+            entry("src/lib/utils.rs", "pub fn helper() {}\n"),
+            entry("readme.md", &"Some words about the project.\n".repeat(5)),
+        ]
+    }
+
+    #[test]
+    fn test_fits_without_trimming() -> Result<()> {
+        let counter = TokenCounter::new("gpt-4o")?;
+        let entries = create_test_entries();
+        let context = OutputContext::default();
+        let measure = measure(&counter, &context);
+        let budgeted = fit_in_order(entries, &[0, 0, 0], &[0, 1, 2], 100_000, &measure)?;
+
+        assert_eq!(budgeted.entries.len(), 3);
+        assert!(budgeted.omitted.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_drops_lowest_priority_files() -> Result<()> {
+        let counter = TokenCounter::new("gpt-4o")?;
+        let entries = create_test_entries();
+        let content_tokens = entries
+            .iter()
+            .map(|e| counter.count_tokens(&e.content))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Budget for everything except the large main.rs
+        let without_main = entries[1..].to_vec();
+        let omitted = vec![PathBuf::from("src/main.rs")];
+        let context = OutputContext {
+            omitted: &omitted,
+            ..Default::default()
+        };
+        let max_tokens =
+            counter.count_tokens(&generate_output(&without_main, "both", &context)?)?;
+
+        let measure = measure(&counter, &context);
+        let budgeted = fit_in_order(entries, &content_tokens, &[1, 2, 0], max_tokens, &measure)?;
+        let output = generate_output(
+            &budgeted.entries,
+            "both",
+            &OutputContext {
+                omitted: &budgeted.omitted,
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(budgeted.omitted, vec![PathBuf::from("src/main.rs")]);
+        assert!(counter.count_tokens(&output)? <= max_tokens);
+        assert!(output.contains("main.rs [omitted]"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_counts_skipped_files_toward_budget() -> Result<()> {
+        let counter = TokenCounter::new("gpt-4o")?;
+        let entries = create_test_entries();
+        let content_tokens = entries
+            .iter()
+            .map(|e| counter.count_tokens(&e.content))
+            .collect::<Result<Vec<_>>>()?;
+        let skipped: Vec<SkippedFile> = (0..3)
+            .map(|i| SkippedFile {
+                path: PathBuf::from(format!("vendor/generated_{}.rs", i)),
+                reason: SkipReason::Generated,
+            })
+            .collect();
+        let context = OutputContext {
+            skipped: &skipped,
+            ..Default::default()
+        };
+
+        // Exactly enough for all files, but only without the skipped list
+        let max_tokens = counter.count_tokens(&generate_output(
+            &entries,
+            "both",
+            &OutputContext::default(),
+        )?)?;
+        let budgeted = fit_in_order(
+            entries,
+            &content_tokens,
+            &[0, 1, 2],
+            max_tokens,
+            &measure(&counter, &context),
+        )?;
+        let output = generate_output(
+            &budgeted.entries,
+            "both",
+            &OutputContext {
+                omitted: &budgeted.omitted,
+                skipped: &skipped,
+                ..Default::default()
+            },
+        )?;
+
+        assert!(!budgeted.omitted.is_empty());
+        assert!(counter.count_tokens(&output)? <= max_tokens);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_ranks() -> Result<()> {
+        let entries = create_test_entries();
+        let patterns = vec!["readme.md".to_string(), "src/lib/**".to_string()];
+        assert_eq!(pattern_ranks(&entries, &patterns)?, vec![2, 1, 0]);
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use crate::config::Config;
+use crate::config::{Config, RedactionConfig};
+use crate::slice::FileSpec;
+use crate::source_detection::CustomTypes;
+use anyhow::bail;
 use clap::{Parser, ValueEnum};
 use std::path::PathBuf;
 
@@ -9,6 +12,62 @@ pub enum TokenizerType {
     HuggingFace,
 }
 
+#[derive(Debug, Clone, ValueEnum)]
+pub enum BudgetPriority {
+    /// Keep the smallest files first
+    Smallest,
+    /// Keep files closest to the root first
+    Depth,
+    /// Keep the most recently modified files first (by git history)
+    Recent,
+    /// Keep files matching --priority patterns first, in pattern order
+    List,
+}
+
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum SortOrder {
+    /// Alphabetical by path
+    Path,
+    /// Smallest files first
+    Size,
+    /// Fewest tokens first
+    Tokens,
+    /// Most recently modified first
+    Mtime,
+    /// READMEs, manifests and entry points first, then by path
+    Important,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum StripKind {
+    /// Comments, keeping string literals that look like them
+    Comments,
+    /// Blank lines (prose keeps one between paragraphs)
+    BlankLines,
+    /// A leading comment with a copyright or license notice
+    LicenseHeaders,
+}
+
+impl StripKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StripKind::Comments => "comments",
+            StripKind::BlankLines => "blank-lines",
+            StripKind::LicenseHeaders => "license-headers",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum DetectionMode {
+    /// Trust file names: extensions, known filenames and shebangs
+    Extension,
+    /// Include any file whose content looks like text
+    Content,
+    /// Require a known file name and text content
+    Both,
+}
+
 #[derive(Parser, Debug)]
 #[command(
     name = "glimpse",
@@ -16,11 +75,20 @@ pub enum TokenizerType {
     version
 )]
 pub struct Cli {
-    /// Directory to analyze
-    #[arg(value_parser = validate_path, default_value = ".")]
+    /// Directory to analyze, or files to include, optionally sliced as
+    /// `src/main.rs:40-90` or `src/main.rs#main`
+    #[arg(value_name = "PATH", default_value = ".")]
+    pub targets: Vec<String>,
+
+    /// The directory to analyze, resolved from the targets
+    #[arg(skip)]
     pub path: PathBuf,
 
-    /// Additional patterns to include (e.g. "*.rs,*.go")
+    /// Only include part of a file: `PATH:START-END` or `PATH#SYMBOL`
+    #[arg(long = "file-spec", value_name = "SPEC", value_parser = FileSpec::parse)]
+    pub file_specs: Vec<FileSpec>,
+
+    /// Only include files matching these patterns (e.g. "*.rs,*.go")
     #[arg(short, long, value_delimiter = ',')]
     pub include: Option<Vec<String>>,
 
@@ -36,7 +104,7 @@ pub struct Cli {
     #[arg(long)]
     pub max_depth: Option<usize>,
 
-    /// Output format (tree, files, or both)
+    /// Output format (tree, files, both, xml, markdown, json, or jsonl)
     #[arg(short, long)]
     pub output: Option<String>,
 
@@ -79,11 +147,166 @@ pub struct Cli {
     /// Interactive mode
     #[arg(long)]
     pub interactive: bool,
+
+    /// Apply a named profile from the config
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Text wrapped around the output, set from the config or profile
+    #[arg(skip)]
+    pub prompt_template: Option<String>,
+
+    /// Extra redaction rules and allowlist, set from the config
+    #[arg(skip)]
+    pub redaction: RedactionConfig,
+
+    /// File types added, remapped or disabled in the config
+    #[arg(skip)]
+    pub custom_types: CustomTypes,
+
+    /// How to decide which files are source files
+    #[arg(long, value_enum, default_value = "both")]
+    pub detect: DetectionMode,
+
+    /// Only include files of these types (e.g. "rust,toml"), see --type-list
+    #[arg(long = "type", value_delimiter = ',')]
+    pub types: Option<Vec<String>>,
+
+    /// Exclude files of these types (e.g. "markdown,json")
+    #[arg(long, value_delimiter = ',')]
+    pub type_not: Option<Vec<String>>,
+
+    /// Print the known types and the files they match, then exit
+    #[arg(long)]
+    pub type_list: bool,
+
+    /// Keep generated, vendored and minified files, which are skipped by default
+    #[arg(long)]
+    pub include_generated: bool,
+
+    /// Remove comments, blank-lines and/or license-headers before counting tokens
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub strip: Vec<StripKind>,
+
+    /// Keep lockfiles as they are instead of summarizing their dependencies
+    #[arg(long)]
+    pub full_lockfiles: bool,
+
+    /// Keep files over --max-size, cut down to their first and last lines
+    #[arg(long)]
+    pub truncate: bool,
+
+    /// Truncate files above this many tokens the same way
+    #[arg(long)]
+    pub max_file_tokens: Option<usize>,
+
+    /// Lines kept from the start of a truncated file
+    #[arg(long, default_value_t = 100)]
+    pub head_lines: usize,
+
+    /// Lines kept from the end of a truncated file
+    #[arg(long, default_value_t = 20)]
+    pub tail_lines: usize,
+
+    /// Prefix each line of file contents with its line number
+    #[arg(long)]
+    pub line_numbers: bool,
+
+    /// Keep secrets such as API keys and private keys instead of redacting them
+    #[arg(long)]
+    pub no_redact: bool,
+
+    /// Replace function bodies with `{ ... }`, keeping signatures, types and docs
+    #[arg(long)]
+    pub skeleton: bool,
+
+    /// List every skipped file and why it was skipped, including ignored paths
+    #[arg(long)]
+    pub report_skipped: bool,
+
+    /// Order of files in the output (ignored in interactive mode)
+    #[arg(long, value_enum, default_value = "path")]
+    pub sort: SortOrder,
+
+    /// Drop whole files, lowest priority first, until the output fits within
+    /// this many tokens (files are never cut short to fit)
+    #[arg(long)]
+    pub max_tokens: Option<usize>,
+
+    /// Which files to keep first when trimming to --max-tokens
+    #[arg(long, value_enum, default_value = "smallest")]
+    pub budget_priority: BudgetPriority,
+
+    /// Patterns to keep first with --budget-priority list (e.g. "src/main.rs,src/**")
+    #[arg(long, value_delimiter = ',')]
+    pub priority: Option<Vec<String>>,
+
+    /// Only include files changed since this git revision (e.g. "main")
+    #[arg(long, group = "changes", conflicts_with_all = ["staged", "uncommitted"])]
+    pub changed_since: Option<String>,
+
+    /// Only include files with staged changes
+    #[arg(long, group = "changes", conflicts_with = "uncommitted")]
+    pub staged: bool,
+
+    /// Only include files with uncommitted changes, including untracked files
+    #[arg(long, group = "changes")]
+    pub uncommitted: bool,
+
+    /// Add the unified diff of each changed file to the output
+    #[arg(long, requires = "changes")]
+    pub diff: bool,
+
+    /// Split the output into numbered parts of at most this many tokens
+    #[arg(long)]
+    pub chunk_tokens: Option<usize>,
 }
 
 impl Cli {
-    pub fn parse_with_config(config: &Config) -> anyhow::Result<Self> {
-        let mut cli = Self::parse();
+    /// Splits the positional targets into the directory to analyze and file
+    /// specs, which are rebased onto that directory
+    pub fn resolve_targets(self) -> anyhow::Result<Self> {
+        let mut cli = self;
+        let mut directory = None;
+        let mut specs = Vec::new();
+        for target in std::mem::take(&mut cli.targets) {
+            let path = PathBuf::from(&target);
+            if path.is_dir() {
+                if let Some(first) = directory.replace(path) {
+                    bail!(
+                        "Only one directory can be analyzed, got '{}' and '{}'",
+                        first.display(),
+                        target
+                    );
+                }
+            } else {
+                specs.push(FileSpec::parse(&target).map_err(anyhow::Error::msg)?);
+            }
+        }
+        specs.append(&mut cli.file_specs);
+
+        cli.path = directory.unwrap_or_else(|| PathBuf::from("."));
+        if !specs.is_empty() && cli.interactive {
+            bail!("File specs can't be combined with --interactive");
+        }
+        cli.file_specs = specs
+            .into_iter()
+            .map(|spec| spec.relative_to(&cli.path))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(cli)
+    }
+
+    /// Fills in everything the CLI left unset from the layered config,
+    /// with the selected profile applied in between
+    pub fn with_config(self, config: &Config) -> anyhow::Result<Self> {
+        let mut cli = self;
+        let config = match &cli.profile {
+            Some(name) => config.clone().with_profile(name)?,
+            None => config.clone(),
+        };
+        cli.prompt_template = config.prompt_template.clone();
+        cli.redaction = config.redaction.clone();
+        cli.custom_types = CustomTypes::from_config(&config);
 
         // Apply config defaults if CLI args aren't specified
         cli.max_size = cli.max_size.or(Some(config.max_size));
@@ -98,6 +321,11 @@ impl Cli {
             cli.exclude = Some(config.default_excludes.clone());
         }
 
+        // CLI includes replace configured ones, since includes narrow the selection
+        if cli.include.is_none() && !config.default_includes.is_empty() {
+            cli.include = Some(config.default_includes.clone());
+        }
+
         // Set default tokenizer if none specified but token counting is enabled
         if !cli.no_tokens && cli.tokenizer.is_none() {
             cli.tokenizer = Some(match config.default_tokenizer.as_str() {
@@ -120,14 +348,3 @@ impl Cli {
         Ok(cli)
     }
 }
-
-fn validate_path(path: &str) -> Result<PathBuf, String> {
-    let path_buf = PathBuf::from(path);
-    if !path_buf.exists() {
-        return Err(format!("Path '{}' does not exist", path));
-    }
-    if !path_buf.is_dir() {
-        return Err(format!("Path '{}' is not a directory", path));
-    }
-    Ok(path_buf)
-}
@@ -1,8 +1,12 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
+use toml::Table;
 
-#[derive(Debug, Serialize, Deserialize)]
+const PROJECT_CONFIG_FILE: &str = ".glimpse.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default = "default_max_size")]
     pub max_size: u64,
@@ -16,11 +20,108 @@ pub struct Config {
     #[serde(default)]
     pub default_excludes: Vec<String>,
 
+    #[serde(default)]
+    pub default_includes: Vec<String>,
+
     #[serde(default = "default_tokenizer_type")]
     pub default_tokenizer: String,
 
     #[serde(default = "default_tokenizer_model")]
     pub default_tokenizer_model: String,
+
+    /// Text wrapped around the output, with `{output}` marking where it goes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_template: Option<String>,
+
+    /// Extensions to treat as source files on top of the built-in table
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_extensions: Vec<String>,
+
+    /// Extensions to never treat as source files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_extensions: Vec<String>,
+
+    /// Exact filenames to treat as source files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_filenames: Vec<String>,
+
+    /// Languages for extensions (keys starting with a dot) or exact filenames
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub language_mappings: BTreeMap<String, String>,
+
+    #[serde(default, skip_serializing_if = "RedactionConfig::is_empty")]
+    pub redaction: RedactionConfig,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Extra secret patterns and paths exempt from redaction
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    /// Regexes keyed by the kind shown in `[REDACTED:kind]`. A capture
+    /// group narrows what is replaced to the group.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rules: BTreeMap<String, String>,
+
+    /// Globs, relative to the analyzed directory, whose files are left as is
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+}
+
+impl RedactionConfig {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.allow.is_empty()
+    }
+}
+
+/// A named set of overrides selected with `--profile`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub includes: Option<Vec<String>>,
+    pub excludes: Option<Vec<String>>,
+    pub max_size: Option<u64>,
+    pub max_depth: Option<usize>,
+    pub output_format: Option<String>,
+    pub tokenizer: Option<String>,
+    pub tokenizer_model: Option<String>,
+    pub prompt_template: Option<String>,
+}
+
+impl Config {
+    /// Applies a profile on top of this config. Profile excludes add to the
+    /// defaults so the usual build and VCS directories stay excluded.
+    pub fn with_profile(mut self, name: &str) -> anyhow::Result<Config> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            anyhow::bail!(
+                "Unknown profile '{}' (available: {})",
+                name,
+                if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available.join(", ")
+                }
+            );
+        };
+
+        if let Some(includes) = profile.includes {
+            self.default_includes = includes;
+        }
+        if let Some(excludes) = profile.excludes {
+            self.default_excludes.extend(excludes);
+        }
+        self.max_size = profile.max_size.unwrap_or(self.max_size);
+        self.max_depth = profile.max_depth.unwrap_or(self.max_depth);
+        self.default_output_format = profile.output_format.unwrap_or(self.default_output_format);
+        self.default_tokenizer = profile.tokenizer.unwrap_or(self.default_tokenizer);
+        self.default_tokenizer_model = profile
+            .tokenizer_model
+            .unwrap_or(self.default_tokenizer_model);
+        self.prompt_template = profile.prompt_template.or(self.prompt_template);
+
+        Ok(self)
+    }
 }
 
 impl Default for Config {
@@ -30,8 +131,16 @@ impl Default for Config {
             max_depth: default_max_depth(),
             default_output_format: default_output_format(),
             default_excludes: default_excludes(),
+            default_includes: Vec::new(),
             default_tokenizer: default_tokenizer_type(),
             default_tokenizer_model: default_tokenizer_model(),
+            prompt_template: None,
+            extra_extensions: Vec::new(),
+            disabled_extensions: Vec::new(),
+            extra_filenames: Vec::new(),
+            language_mappings: BTreeMap::new(),
+            redaction: RedactionConfig::default(),
+            profiles: BTreeMap::new(),
         }
     }
 }
@@ -71,20 +180,58 @@ fn default_excludes() -> Vec<String> {
     ]
 }
 
-pub fn load_config() -> anyhow::Result<Config> {
+/// Loads the global config, layered field by field with the nearest
+/// `.glimpse.toml` found from `target` upwards
+pub fn load_config(target: &Path) -> anyhow::Result<Config> {
+    let mut table = load_global_table()?;
+
+    if let Some(project_path) = find_project_config(target) {
+        let project_str = std::fs::read_to_string(&project_path)?;
+        let project: Table = toml::from_str(&project_str).map_err(|e| {
+            anyhow::anyhow!("Invalid config in '{}': {}", project_path.display(), e)
+        })?;
+        merge_tables(&mut table, project);
+    }
+
+    Ok(toml::Value::Table(table).try_into()?)
+}
+
+fn load_global_table() -> anyhow::Result<Table> {
     let config_path = get_config_path()?;
 
     if !config_path.exists() {
         let config = Config::default();
         std::fs::create_dir_all(config_path.parent().unwrap())?;
         let config_str = toml::to_string_pretty(&config)?;
-        std::fs::write(&config_path, config_str)?;
-        return Ok(config);
+        std::fs::write(&config_path, &config_str)?;
+        return Ok(toml::from_str(&config_str)?);
     }
 
     let config_str = std::fs::read_to_string(config_path)?;
-    let config: Config = toml::from_str(&config_str)?;
-    Ok(config)
+    Ok(toml::from_str(&config_str)?)
+}
+
+fn find_project_config(target: &Path) -> Option<PathBuf> {
+    let start = target.canonicalize().ok()?;
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Overlays `overlay` onto `base`, recursing into tables so only the keys
+/// that are set in the overlay replace their base values
+fn merge_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
 }
 
 fn get_config_path() -> anyhow::Result<PathBuf> {
@@ -93,3 +240,89 @@ fn get_config_path() -> anyhow::Result<PathBuf> {
         .join("glimpse");
     Ok(config_dir.join("config.toml"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_tables_by_field() {
+        let mut base: Table = toml::from_str(
+            r#"
+            max_size = 100
+            default_output_format = "both"
+            default_excludes = ["**/target/**"]
+            "#,
+        )
+        .unwrap();
+        let project: Table = toml::from_str(
+            r#"
+            default_output_format = "xml"
+            default_excludes = ["**/fixtures/**"]
+            default_includes = ["*.rs"]
+            "#,
+        )
+        .unwrap();
+
+        merge_tables(&mut base, project);
+        let config: Config = toml::Value::Table(base).try_into().unwrap();
+
+        assert_eq!(config.max_size, 100);
+        assert_eq!(config.default_output_format, "xml");
+        assert_eq!(config.default_excludes, vec!["**/fixtures/**"]);
+        assert_eq!(config.default_includes, vec!["*.rs"]);
+        assert_eq!(config.default_tokenizer, "tiktoken");
+    }
+
+    #[test]
+    fn test_profiles() {
+        let config: Config = toml::from_str(
+            r#"
+            max_depth = 20
+            default_excludes = ["**/target/**"]
+
+            [profiles.backend]
+            includes = ["*.rs", "*.sql"]
+            excludes = ["**/migrations/**"]
+            max_depth = 5
+            output_format = "markdown"
+            prompt_template = "Review this backend code:\n{output}"
+
+            [profiles.frontend]
+            includes = ["*.ts", "*.tsx"]
+            "#,
+        )
+        .unwrap();
+
+        let backend = config.clone().with_profile("backend").unwrap();
+        assert_eq!(backend.default_includes, vec!["*.rs", "*.sql"]);
+        assert_eq!(
+            backend.default_excludes,
+            vec!["**/target/**", "**/migrations/**"]
+        );
+        assert_eq!(backend.max_depth, 5);
+        assert_eq!(backend.max_size, default_max_size());
+        assert_eq!(backend.default_output_format, "markdown");
+        assert!(backend.prompt_template.unwrap().contains("{output}"));
+
+        let error = config.with_profile("mobile").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown profile 'mobile' (available: backend, frontend)"
+        );
+    }
+
+    #[test]
+    fn test_find_project_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("crates/app/src");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert!(find_project_config(&nested).is_none_or(|p| !p.starts_with(dir.path())));
+
+        let config_path = dir.path().join("crates").join(PROJECT_CONFIG_FILE);
+        std::fs::write(&config_path, "max_depth = 3\n").unwrap();
+
+        let found = find_project_config(&nested).unwrap();
+        assert_eq!(found, config_path.canonicalize().unwrap());
+    }
+}
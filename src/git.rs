@@ -0,0 +1,204 @@
+use anyhow::{anyhow, bail, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Which working tree changes to select files from
+pub enum ChangeSet {
+    /// Everything changed since the merge base with a revision, including uncommitted work
+    Since(String),
+    /// Changes staged in the index
+    Staged,
+    /// Staged, unstaged and untracked changes
+    Uncommitted,
+}
+
+impl ChangeSet {
+    /// Diffs against the merge base so upstream commits made after branching are left out
+    pub fn since(root: &Path, rev: &str) -> Result<Self> {
+        let commit = format!("{}^{{commit}}", rev);
+        if run_git(
+            root,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                "--end-of-options",
+                &commit,
+            ],
+        )
+        .is_err()
+        {
+            bail!("Unknown git revision '{}'", rev);
+        }
+        let base = run_git(root, &["merge-base", "--end-of-options", rev, "HEAD"])
+            .map_err(|_| anyhow!("'{}' has no common history with HEAD", rev))?;
+        Ok(ChangeSet::Since(base.trim().to_string()))
+    }
+
+    fn diff_args(&self) -> Vec<&str> {
+        match self {
+            ChangeSet::Since(base) => vec!["diff", base.as_str()],
+            ChangeSet::Staged => vec!["diff", "--cached"],
+            ChangeSet::Uncommitted => vec!["diff", "HEAD"],
+        }
+    }
+
+    fn includes_untracked(&self) -> bool {
+        !matches!(self, ChangeSet::Staged)
+    }
+}
+
+/// Files touched by the change set, relative to `root`
+pub fn changed_files(root: &Path, changes: &ChangeSet) -> Result<HashSet<PathBuf>> {
+    let mut args = changes.diff_args();
+    args.extend(["--name-only", "--relative"]);
+    let mut files: HashSet<PathBuf> = run_git(root, &args)?.lines().map(PathBuf::from).collect();
+
+    if changes.includes_untracked() {
+        let untracked = run_git(root, &["ls-files", "--others", "--exclude-standard"])?;
+        files.extend(untracked.lines().map(PathBuf::from));
+    }
+
+    Ok(files)
+}
+
+/// Unified diff of a single file within the change set, empty for untracked files
+pub fn file_diff(root: &Path, changes: &ChangeSet, path: &Path) -> Result<String> {
+    let mut args = changes.diff_args();
+    args.extend(["--relative", "--"]);
+    let path = path.to_string_lossy();
+    args.push(&path);
+    run_git(root, &args)
+}
+
+/// Runs git inside `root` and returns its stdout
+fn run_git(root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()?;
+
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Maps each file under `root` to the unix time of the last commit that touched it.
+/// Paths are relative to `root`.
+pub fn last_commit_times(root: &Path) -> Result<HashMap<PathBuf, i64>> {
+    let log = run_git(
+        root,
+        &["log", "--format=%x00%ct", "--name-only", "--relative"],
+    )?;
+    let mut times = HashMap::new();
+    let mut current_time = 0;
+
+    for line in log.lines() {
+        if let Some(timestamp) = line.strip_prefix('\0') {
+            current_time = timestamp.trim().parse()?;
+        } else if !line.is_empty() {
+            // The log is newest first, so the first sighting wins
+            times.entry(PathBuf::from(line)).or_insert(current_time);
+        }
+    }
+
+    Ok(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn git(root: &Path, args: &[&str]) {
+        run_git(root, args).unwrap();
+    }
+
+    fn commit_all(root: &Path, message: &str) {
+        git(root, &["add", "-A"]);
+        git(
+            root,
+            &[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-qm",
+                message,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_change_sets() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.join("b.rs"), "fn b() {}\n").unwrap();
+        commit_all(root, "initial");
+
+        fs::write(root.join("a.rs"), "fn a() { 1 }\n").unwrap();
+        fs::write(root.join("c.rs"), "fn c() {}\n").unwrap();
+        fs::write(root.join("b.rs"), "fn b() { 2 }\n").unwrap();
+        git(root, &["add", "b.rs"]);
+
+        let staged = changed_files(root, &ChangeSet::Staged).unwrap();
+        assert_eq!(staged, HashSet::from([PathBuf::from("b.rs")]));
+
+        let uncommitted = changed_files(root, &ChangeSet::Uncommitted).unwrap();
+        let expected = ["a.rs", "b.rs", "c.rs"].map(PathBuf::from);
+        assert_eq!(uncommitted, HashSet::from(expected));
+
+        let diff = file_diff(root, &ChangeSet::Uncommitted, Path::new("a.rs")).unwrap();
+        assert!(diff.contains("-fn a() {}\n+fn a() { 1 }\n"));
+        let untracked = file_diff(root, &ChangeSet::Uncommitted, Path::new("c.rs")).unwrap();
+        assert!(untracked.is_empty());
+    }
+
+    #[test]
+    fn test_changed_since() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+        commit_all(root, "initial");
+        git(root, &["tag", "base"]);
+
+        fs::create_dir(root.join("src")).unwrap();
+        fs::write(root.join("src/d.rs"), "fn d() {}\n").unwrap();
+        commit_all(root, "add d");
+
+        let changes = ChangeSet::since(root, "base").unwrap();
+        let changed = changed_files(root, &changes).unwrap();
+        assert_eq!(changed, HashSet::from([PathBuf::from("src/d.rs")]));
+
+        let times = last_commit_times(root).unwrap();
+        assert!(times[Path::new("src/d.rs")] >= times[Path::new("a.rs")]);
+    }
+
+    #[test]
+    fn test_changed_since_unknown_revision() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+        commit_all(root, "initial");
+
+        let err = ChangeSet::since(root, "no-such-branch").err().unwrap();
+        assert_eq!(err.to_string(), "Unknown git revision 'no-such-branch'");
+        assert!(ChangeSet::since(root, "--output=x").is_err());
+        assert!(!root.join("x").exists());
+    }
+}
@@ -0,0 +1,633 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+const LOCKFILE_NAMES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "poetry.lock",
+    "go.sum",
+];
+
+pub fn is_lockfile(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| LOCKFILE_NAMES.contains(&name))
+}
+
+/// Resolved versions of the packages a project depends on directly, and a
+/// count of everything pulled in through them
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    direct: BTreeMap<String, BTreeSet<String>>,
+    transitive: usize,
+}
+
+/// Replaces a lockfile's content with its direct dependencies and resolved
+/// versions. Returns `None` for other files, or lockfiles that don't parse,
+/// so they are kept as they are.
+pub fn summarize(path: &Path, content: &str) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    // Lockfiles that don't record the project's own requirements leave them
+    // to the manifest next to them
+    let manifest = |file_name| fs::read_to_string(path.with_file_name(file_name)).ok();
+    let summary = match name {
+        "Cargo.lock" => cargo_lock(content)?,
+        "package-lock.json" => package_lock(content, manifest("package.json"))?,
+        "yarn.lock" => yarn_lock(content, manifest("package.json"))?,
+        "poetry.lock" => poetry_lock(content, manifest("pyproject.toml"))?,
+        // go.sum has no dependency graph, the direct requirements live in go.mod
+        "go.sum" => go_sum(content, manifest("go.mod"))?,
+        _ => return None,
+    };
+
+    let mut output = format!(
+        "# {} summary: {} direct dependencies, {} transitive\n",
+        name,
+        summary.direct.len(),
+        summary.transitive
+    );
+    for (package, versions) in &summary.direct {
+        let versions: Vec<&str> = versions.iter().map(String::as_str).collect();
+        output.push_str(&format!("{} {}\n", package, versions.join(", ")));
+    }
+    Some(output)
+}
+
+/// Builds a summary from every locked `(name, version)` and the names taken
+/// to be direct dependencies
+fn summary_of(packages: &[(String, String)], direct: &HashSet<String>) -> Summary {
+    let mut summary = Summary::default();
+    let mut transitive = HashSet::new();
+    for (name, version) in packages {
+        if direct.contains(name) {
+            summary
+                .direct
+                .entry(name.clone())
+                .or_default()
+                .insert(version.clone());
+        } else {
+            transitive.insert((name, version));
+        }
+    }
+    summary.transitive = transitive.len();
+    summary
+}
+
+/// Without a manifest, a lockfile only says which packages other packages
+/// need, so the rest must be what the project asked for. This misses direct
+/// dependencies that something else also requires.
+fn unrequired(packages: &[(String, String)], required: &HashSet<String>) -> HashSet<String> {
+    packages
+        .iter()
+        .map(|(name, _)| name.clone())
+        .filter(|name| !required.contains(name))
+        .collect()
+}
+
+/// Workspace members are the packages without a `source`, and their
+/// dependencies are the direct ones
+fn cargo_lock(content: &str) -> Option<Summary> {
+    let lock: toml::Table = toml::from_str(content).ok()?;
+    let mut packages = Vec::new();
+    let mut members = HashSet::new();
+    let mut direct = HashSet::new();
+
+    for package in lock.get("package")?.as_array()? {
+        let name = package.get("name")?.as_str()?.to_string();
+        let version = package.get("version")?.as_str()?.to_string();
+        if package.get("source").is_some() {
+            packages.push((name, version));
+            continue;
+        }
+
+        let dependencies = package.get("dependencies").and_then(|d| d.as_array());
+        for dependency in dependencies.into_iter().flatten() {
+            // Entries are `name`, or `name version` when several versions are locked
+            let dependency_name = dependency.as_str()?.split_whitespace().next()?;
+            direct.insert(dependency_name.to_string());
+        }
+        members.insert(name);
+    }
+
+    // Path dependencies between workspace members aren't external dependencies
+    direct.retain(|name| !members.contains(name));
+    Some(summary_of(&packages, &direct))
+}
+
+fn package_lock(content: &str, package_json: Option<String>) -> Option<Summary> {
+    let lock: Value = serde_json::from_str(content).ok()?;
+
+    // Lockfile v2 and later lists every installed package under `packages`,
+    // with the root's own requirements under the empty key
+    if let Some(packages) = lock.get("packages").and_then(Value::as_object) {
+        let root = packages.get("")?;
+        let direct = npm_dependencies(root);
+
+        let mut summary = Summary::default();
+        for name in direct {
+            let key = format!("node_modules/{}", name);
+            let version = packages.get(&key).and_then(|p| p.get("version"));
+            if let Some(version) = version.and_then(Value::as_str) {
+                summary
+                    .direct
+                    .entry(name)
+                    .or_default()
+                    .insert(version.to_string());
+            }
+        }
+
+        // Nested installs of the same package at different versions count separately
+        let installed = packages
+            .keys()
+            .filter(|key| key.contains("node_modules/"))
+            .count();
+        summary.transitive = installed.saturating_sub(summary.direct.len());
+        return Some(summary);
+    }
+
+    // Lockfile v1 nests packages under `dependencies` with `requires` edges
+    fn walk(deps: &serde_json::Map<String, Value>, packages: &mut Vec<(String, String)>) {
+        for (name, package) in deps {
+            if let Some(version) = package.get("version").and_then(Value::as_str) {
+                packages.push((name.clone(), version.to_string()));
+            }
+            if let Some(nested) = package.get("dependencies").and_then(Value::as_object) {
+                walk(nested, packages);
+            }
+        }
+    }
+
+    let top_level = lock.get("dependencies")?.as_object()?;
+    let mut packages = Vec::new();
+    walk(top_level, &mut packages);
+    let required: HashSet<String> = top_level
+        .values()
+        .filter_map(|package| package.get("requires").and_then(Value::as_object))
+        .flat_map(|requires| requires.keys().cloned())
+        .collect();
+
+    let direct = package_json
+        .as_deref()
+        .and_then(package_json_dependencies)
+        .unwrap_or_else(|| unrequired(&packages, &required));
+    Some(summary_of(&packages, &direct))
+}
+
+/// Names under a package.json's dependency fields, also the shape of the
+/// root entry in package-lock.json
+fn npm_dependencies(package: &Value) -> HashSet<String> {
+    [
+        "dependencies",
+        "devDependencies",
+        "optionalDependencies",
+        "peerDependencies",
+    ]
+    .iter()
+    .filter_map(|field| package.get(field).and_then(Value::as_object))
+    .flat_map(|deps| deps.keys().cloned())
+    .collect()
+}
+
+/// `None` when the manifest doesn't parse or names nothing, as in a
+/// workspace root, leaving the lockfile to go on
+fn package_json_dependencies(package_json: &str) -> Option<HashSet<String>> {
+    let package: Value = serde_json::from_str(package_json).ok()?;
+    Some(npm_dependencies(&package)).filter(|direct| !direct.is_empty())
+}
+
+/// Handles both the classic format (`version "1.0.0"`) and Berry's YAML
+/// (`version: 1.0.0`). Berry lists the project itself as a `@workspace:`
+/// entry, whose dependencies are the direct ones.
+fn yarn_lock(content: &str, package_json: Option<String>) -> Option<Summary> {
+    let mut packages = Vec::new();
+    let mut required = HashSet::new();
+    let mut workspace_deps = HashSet::new();
+
+    let mut current: Option<(String, bool)> = None;
+    let mut in_dependencies = false;
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+
+        if indent == 0 {
+            // `"lodash@^4.17.0", lodash@^4.17.21:` names one package by its ranges
+            let spec = line.trim_end_matches(':').split(',').next()?.trim();
+            let spec = spec.trim_matches('"');
+            current = package_name(spec).map(|name| (name, spec.contains("@workspace:")));
+            in_dependencies = false;
+            continue;
+        }
+        let Some((name, is_workspace)) = &current else {
+            continue;
+        };
+
+        if indent <= 2 {
+            in_dependencies = matches!(
+                line,
+                "dependencies:" | "optionalDependencies:" | "peerDependencies:"
+            );
+            if let Some(version) = line
+                .strip_prefix("version ")
+                .or_else(|| line.strip_prefix("version: "))
+            {
+                if !is_workspace {
+                    let version = version.trim_matches('"').to_string();
+                    packages.push((name.clone(), version));
+                }
+            }
+        } else if in_dependencies {
+            let dependency = line.split([' ', ':']).next()?.trim_matches('"');
+            if *is_workspace {
+                workspace_deps.insert(dependency.to_string());
+            } else {
+                required.insert(dependency.to_string());
+            }
+        }
+    }
+
+    let direct = if workspace_deps.is_empty() {
+        package_json
+            .as_deref()
+            .and_then(package_json_dependencies)
+            .unwrap_or_else(|| unrequired(&packages, &required))
+    } else {
+        workspace_deps
+    };
+    Some(summary_of(&packages, &direct))
+}
+
+/// Splits `@scope/name@^1.0.0` into its package name
+fn package_name(spec: &str) -> Option<String> {
+    let at = spec.get(1..)?.find('@')? + 1;
+    Some(spec[..at].to_string())
+}
+
+fn poetry_lock(content: &str, pyproject: Option<String>) -> Option<Summary> {
+    let lock: toml::Table = toml::from_str(content).ok()?;
+
+    let mut packages = Vec::new();
+    let mut required = HashSet::new();
+    for package in lock.get("package")?.as_array()? {
+        let name = normalize(package.get("name")?.as_str()?);
+        let version = package.get("version")?.as_str()?.to_string();
+        packages.push((name, version));
+
+        let dependencies = package.get("dependencies").and_then(|d| d.as_table());
+        for dependency in dependencies.into_iter().flat_map(|deps| deps.keys()) {
+            required.insert(normalize(dependency));
+        }
+    }
+
+    let direct = pyproject
+        .as_deref()
+        .and_then(pyproject_dependencies)
+        .unwrap_or_else(|| unrequired(&packages, &required));
+    Some(summary_of(&packages, &direct))
+}
+
+/// PEP 503 normalization, dependency tables don't always match the package's spelling
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Requirements from Poetry's own tables and dependency groups, or from the
+/// standard `[project]` table, where entries are PEP 508 strings like
+/// `requests[socks]>=2.31`. `None` when it names nothing.
+fn pyproject_dependencies(pyproject: &str) -> Option<HashSet<String>> {
+    let pyproject: toml::Table = toml::from_str(pyproject).ok()?;
+    let mut direct = HashSet::new();
+
+    if let Some(poetry) = pyproject.get("tool").and_then(|tool| tool.get("poetry")) {
+        let groups = poetry.get("group").and_then(|g| g.as_table());
+        let tables = ["dependencies", "dev-dependencies"]
+            .iter()
+            .filter_map(|field| poetry.get(field))
+            .chain(
+                groups
+                    .into_iter()
+                    .flat_map(|groups| groups.values())
+                    .filter_map(|group| group.get("dependencies")),
+            );
+        for table in tables.filter_map(|table| table.as_table()) {
+            direct.extend(
+                table
+                    .keys()
+                    .filter(|name| *name != "python")
+                    .map(|name| normalize(name)),
+            );
+        }
+    }
+
+    if let Some(project) = pyproject.get("project") {
+        let optional = project
+            .get("optional-dependencies")
+            .and_then(|o| o.as_table());
+        let requirements = project
+            .get("dependencies")
+            .and_then(|d| d.as_array())
+            .into_iter()
+            .chain(
+                optional
+                    .into_iter()
+                    .flat_map(|o| o.values())
+                    .filter_map(|d| d.as_array()),
+            )
+            .flatten()
+            .filter_map(|requirement| requirement.as_str());
+        for requirement in requirements {
+            let end = requirement
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+                .unwrap_or(requirement.len());
+            if end > 0 {
+                direct.insert(normalize(&requirement[..end]));
+            }
+        }
+    }
+
+    Some(direct).filter(|direct| !direct.is_empty())
+}
+
+/// Lines are `module version hash`, plus `module version/go.mod hash` for
+/// modules whose go.mod was consulted during resolution but never downloaded
+fn go_sum(content: &str, go_mod: Option<String>) -> Option<Summary> {
+    let mut packages = Vec::new();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(module), Some(version)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if !version.ends_with("/go.mod") {
+            packages.push((module.to_string(), version.to_string()));
+        }
+    }
+
+    // Without a go.mod there is nothing to tell direct modules apart, so list them all
+    let direct = match go_mod {
+        Some(go_mod) => direct_requirements(&go_mod),
+        None => packages.iter().map(|(name, _)| name.clone()).collect(),
+    };
+    Some(summary_of(&packages, &direct))
+}
+
+/// Modules required in go.mod, leaving out those marked `// indirect`
+fn direct_requirements(go_mod: &str) -> HashSet<String> {
+    let mut direct = HashSet::new();
+    let mut in_block = false;
+    for line in go_mod.lines() {
+        let line = line.trim();
+        let requirement = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(rest) = line.strip_prefix("require ") {
+            rest
+        } else {
+            continue;
+        };
+
+        if !requirement.contains("// indirect") {
+            if let Some(module) = requirement.split_whitespace().next() {
+                direct.insert(module.to_string());
+            }
+        }
+    }
+    direct
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn direct(summary: &Summary) -> Vec<String> {
+        summary
+            .direct
+            .iter()
+            .map(|(name, versions)| {
+                let versions: Vec<&str> = versions.iter().map(String::as_str).collect();
+                format!("{} {}", name, versions.join(", "))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cargo_lock() {
+        let summary = cargo_lock(
+            r#"
+            version = 3
+
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = ["serde", "rand 0.8.5", "core"]
+
+            [[package]]
+            name = "core"
+            version = "0.1.0"
+
+            [[package]]
+            name = "serde"
+            version = "1.0.200"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+
+            [[package]]
+            name = "rand"
+            version = "0.8.5"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            dependencies = ["rand_core"]
+
+            [[package]]
+            name = "rand_core"
+            version = "0.6.4"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(direct(&summary), vec!["rand 0.8.5", "serde 1.0.200"]);
+        assert_eq!(summary.transitive, 1);
+    }
+
+    #[test]
+    fn test_package_lock() {
+        let summary = package_lock(
+            r#"{
+              "lockfileVersion": 3,
+              "packages": {
+                "": { "dependencies": { "react": "^18.2.0" }, "devDependencies": { "vite": "^5.0.0" } },
+                "node_modules/react": { "version": "18.2.0" },
+                "node_modules/loose-envify": { "version": "1.4.0" },
+                "node_modules/vite": { "version": "5.1.0" },
+                "node_modules/vite/node_modules/esbuild": { "version": "0.19.0" }
+              }
+            }"#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(direct(&summary), vec!["react 18.2.0", "vite 5.1.0"]);
+        assert_eq!(summary.transitive, 2);
+
+        // `debug` is both a direct dependency and one of express's
+        let v1 = r#"{
+              "lockfileVersion": 1,
+              "dependencies": {
+                "express": { "version": "4.18.2", "requires": { "debug": "2.6.9" } },
+                "debug": { "version": "2.6.9" },
+                "ms": { "version": "2.0.0" }
+              }
+            }"#;
+        let package_json = r#"{ "dependencies": { "express": "^4.18.2", "debug": "^2.6.9" } }"#;
+        let summary = package_lock(v1, Some(package_json.to_string())).unwrap();
+        assert_eq!(direct(&summary), vec!["debug 2.6.9", "express 4.18.2"]);
+        assert_eq!(summary.transitive, 1);
+
+        // Without package.json, anything another package requires looks transitive
+        let summary = package_lock(v1, None).unwrap();
+        assert_eq!(direct(&summary), vec!["express 4.18.2", "ms 2.0.0"]);
+        assert_eq!(summary.transitive, 1);
+    }
+
+    #[test]
+    fn test_yarn_lock() {
+        let classic = r#"
+# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/code-frame@^7.0.0", "@babel/code-frame@^7.10.4":
+  version "7.12.13"
+  resolved "https://registry.yarnpkg.com/@babel/code-frame/-/code-frame-7.12.13.tgz"
+  dependencies:
+    "@babel/highlight" "^7.12.13"
+
+"@babel/highlight@^7.12.13":
+  version "7.13.10"
+"#;
+        let summary = yarn_lock(classic, None).unwrap();
+        assert_eq!(direct(&summary), vec!["@babel/code-frame 7.12.13"]);
+        assert_eq!(summary.transitive, 1);
+
+        let package_json = r#"{
+          "dependencies": { "@babel/code-frame": "^7.10.4" },
+          "devDependencies": { "@babel/highlight": "^7.12.13" }
+        }"#;
+        let summary = yarn_lock(classic, Some(package_json.to_string())).unwrap();
+        assert_eq!(
+            direct(&summary),
+            vec!["@babel/code-frame 7.12.13", "@babel/highlight 7.13.10"]
+        );
+        assert_eq!(summary.transitive, 0);
+
+        let berry = r#"
+__metadata:
+  version: 6
+
+"app@workspace:.":
+  version: 0.0.0-use.local
+  dependencies:
+    lodash: ^4.17.21
+
+"lodash@npm:^4.17.21":
+  version: 4.17.21
+  resolution: "lodash@npm:4.17.21"
+"#;
+        let summary = yarn_lock(berry, None).unwrap();
+        assert_eq!(direct(&summary), vec!["lodash 4.17.21"]);
+        assert_eq!(summary.transitive, 0);
+    }
+
+    #[test]
+    fn test_poetry_lock() {
+        let lock = r#"
+            [[package]]
+            name = "requests"
+            version = "2.31.0"
+
+            [package.dependencies]
+            charset-normalizer = ">=2,<4"
+
+            [[package]]
+            name = "charset_normalizer"
+            version = "3.3.2"
+
+            [[package]]
+            name = "pytest"
+            version = "8.0.0"
+            "#;
+        let summary = poetry_lock(lock, None).unwrap();
+        assert_eq!(direct(&summary), vec!["pytest 8.0.0", "requests 2.31.0"]);
+        assert_eq!(summary.transitive, 1);
+
+        let poetry = r#"
+            [tool.poetry.dependencies]
+            python = "^3.11"
+            requests = "^2.31"
+            Charset_Normalizer = "^3.3"
+
+            [tool.poetry.group.dev.dependencies]
+            pytest = "^8.0"
+            "#;
+        let summary = poetry_lock(lock, Some(poetry.to_string())).unwrap();
+        assert_eq!(
+            direct(&summary),
+            vec![
+                "charset-normalizer 3.3.2",
+                "pytest 8.0.0",
+                "requests 2.31.0"
+            ]
+        );
+        assert_eq!(summary.transitive, 0);
+
+        let pep_621 = r#"
+            [project]
+            dependencies = ["requests[socks]>=2.31", "charset-normalizer"]
+            "#;
+        let summary = poetry_lock(lock, Some(pep_621.to_string())).unwrap();
+        assert_eq!(
+            direct(&summary),
+            vec!["charset-normalizer 3.3.2", "requests 2.31.0"]
+        );
+        assert_eq!(summary.transitive, 1);
+    }
+
+    #[test]
+    fn test_go_sum() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/app\n\nrequire (\n\tgithub.com/spf13/cobra v1.8.0\n\tgithub.com/spf13/pflag v1.0.5 // indirect\n)\n",
+        )?;
+        let go_sum = "\
+github.com/spf13/cobra v1.8.0 h1:abc=
+github.com/spf13/cobra v1.8.0/go.mod h1:def=
+github.com/spf13/pflag v1.0.5 h1:ghi=
+github.com/spf13/pflag v1.0.5/go.mod h1:jkl=
+gopkg.in/yaml.v3 v3.0.1/go.mod h1:mno=
+";
+        let summary = summarize(&dir.path().join("go.sum"), go_sum).unwrap();
+        assert_eq!(
+            summary,
+            "# go.sum summary: 1 direct dependencies, 1 transitive\ngithub.com/spf13/cobra v1.8.0\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_only_summarizes_lockfiles() {
+        assert!(is_lockfile(Path::new("web/package-lock.json")));
+        assert!(!is_lockfile(Path::new("package.json")));
+        assert!(summarize(Path::new("package.json"), "{}").is_none());
+        assert!(summarize(Path::new("Cargo.lock"), "not toml [").is_none());
+    }
+}
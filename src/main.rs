@@ -1,21 +1,41 @@
 mod analyzer;
+mod budget;
 mod cli;
 mod config;
 mod file_picker;
+mod git;
+mod lockfile;
 mod output;
+mod redact;
+mod skeleton;
+mod slice;
 mod source_detection;
+mod strip;
 mod tokenizer;
 
 use crate::analyzer::process_directory;
 use crate::cli::Cli;
 use crate::config::load_config;
+use clap::Parser;
 
 fn main() -> anyhow::Result<()> {
-    // Load config first
-    let config = load_config()?;
+    // Parse CLI args first, the project config is found from the target path
+    let cli = Cli::parse().resolve_targets()?;
 
-    // Parse CLI args with config as context
-    let args = Cli::parse_with_config(&config)?;
+    // Load the global config layered with the project's .glimpse.toml
+    let config = load_config(&cli.path)?;
+
+    if cli.type_list {
+        let custom_types = source_detection::CustomTypes::from_config(&config);
+        for (name, globs) in source_detection::known_types(&custom_types) {
+            let globs: Vec<_> = globs.into_iter().collect();
+            println!("{}: {}", name, globs.join(", "));
+        }
+        return Ok(());
+    }
+
+    // CLI args take precedence over both configs
+    let args = cli.with_config(&config)?;
 
     process_directory(&args)
 }
@@ -1,21 +1,207 @@
-use crate::{cli::Cli, tokenizer::TokenCounter};
-use anyhow::Result;
-use std::{fs, path::PathBuf};
+use crate::{
+    cli::{Cli, StripKind},
+    source_detection::Language,
+    tokenizer::{TokenCount, TokenCounter},
+};
+use anyhow::{bail, Result};
+use rayon::prelude::*;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug, Clone)]
 pub struct FileEntry {
     pub path: PathBuf,
     pub content: String,
     pub size: u64,
+    /// Unified diff of the file's changes, when selecting by git changes
+    pub diff: Option<String>,
+    /// Detected once when the file is read, with the config's custom types
+    pub language: Option<Language>,
+    /// Where the content's lines are in the file, for `--line-numbers`
+    pub origin: LineOrigin,
 }
 
-pub fn generate_output(entries: &[FileEntry], format: &str) -> Result<String> {
+/// Tracks the file line behind each line of an entry's content, so line
+/// numbers still point into the file after slicing, stripping or skeletons
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum LineOrigin {
+    /// Line `i` is line `i + 1` of the file
+    #[default]
+    Unchanged,
+    /// The file's line number for each line, `None` for lines standing in for
+    /// others, such as elision markers
+    Mapped(Vec<Option<usize>>),
+    /// Written by glimpse rather than taken from the file, like a lockfile summary
+    Synthetic,
+}
+
+/// Replaces the `start..end` bytes of a file's content with the string.
+/// Edits are applied together, sorted by position and never overlapping.
+pub type Edit = (usize, usize, String);
+
+impl FileEntry {
+    /// Applies edits to the content, carrying each line's file line number along
+    pub fn splice(&mut self, edits: &[Edit]) {
+        if edits.is_empty() {
+            return;
+        }
+        let (content, sources) = splice(&self.content, edits);
+        self.origin = match &self.origin {
+            LineOrigin::Unchanged => {
+                LineOrigin::Mapped(sources.iter().map(|line| line.map(|i| i + 1)).collect())
+            }
+            LineOrigin::Mapped(numbers) => LineOrigin::Mapped(
+                sources
+                    .iter()
+                    .map(|line| line.and_then(|i| numbers.get(i).copied().flatten()))
+                    .collect(),
+            ),
+            LineOrigin::Synthetic => LineOrigin::Synthetic,
+        };
+        self.content = content;
+    }
+}
+
+/// Applies edits to `content`. Also returns, for each line of the result, the
+/// 0-based line of `content` it starts on, or `None` when it starts inside a
+/// replacement.
+pub fn splice(content: &str, edits: &[Edit]) -> (String, Vec<Option<usize>>) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    let mut pieces: Vec<(&str, Option<usize>)> = Vec::with_capacity(edits.len() * 2 + 1);
+    let mut last = 0;
+    for (start, end, replacement) in edits {
+        pieces.push((&content[last..*start], Some(last)));
+        pieces.push((replacement, None));
+        last = *end;
+    }
+    pieces.push((&content[last..], Some(last)));
+
+    let mut output = String::with_capacity(content.len());
+    let mut sources = Vec::new();
+    let mut line_start = true;
+    for (text, offset) in pieces {
+        for (i, byte) in text.bytes().enumerate() {
+            if line_start {
+                let line =
+                    offset.map(|offset| line_starts.partition_point(|&s| s <= offset + i) - 1);
+                sources.push(line);
+            }
+            line_start = byte == b'\n';
+        }
+        output.push_str(text);
+    }
+    (output, sources)
+}
+
+/// Why a file was left out of the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    Binary,
+    TooLarge,
+    UnknownType,
+    /// Generated, vendored or minified
+    Generated,
+    Ignored,
+    ReadError,
+}
+
+impl SkipReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SkipReason::Binary => "binary",
+            SkipReason::TooLarge => "too large",
+            SkipReason::UnknownType => "unknown type",
+            SkipReason::Generated => "generated or vendored",
+            SkipReason::Ignored => "ignored",
+            SkipReason::ReadError => "read error",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "file")]
+struct FileRecord<'a> {
+    path: String,
+    size: u64,
+    language: Option<&'static str>,
+    tokens: Option<usize>,
+    lines: usize,
+    sha256: String,
+    content: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diff: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "summary")]
+struct SummaryRecord {
+    total_files: usize,
+    total_size: u64,
+    total_tokens: Option<usize>,
+    omitted: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    truncated: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<SkippedRecord>,
+    top_files: Vec<TokenRecord>,
+}
+
+#[derive(Serialize)]
+struct SkippedRecord {
+    path: String,
+    reason: SkipReason,
+}
+
+#[derive(Serialize)]
+struct TokenRecord {
+    path: String,
+    tokens: usize,
+}
+
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+    files: Vec<FileRecord<'a>>,
+    summary: SummaryRecord,
+}
+
+/// Extra state besides the file entries that shapes the rendered output
+#[derive(Default)]
+pub struct OutputContext<'a> {
+    pub token_count: Option<&'a TokenCount>,
+    /// Files left out of the contents but still listed in the tree
+    pub omitted: &'a [PathBuf],
+    /// Files skipped during the scan, listed in the summary
+    pub skipped: &'a [SkippedFile],
+    /// Files cut down to their first and last lines, marked in the tree
+    pub truncated: &'a [PathBuf],
+}
+
+pub fn generate_output(
+    entries: &[FileEntry],
+    format: &str,
+    context: &OutputContext,
+) -> Result<String> {
     let mut output = String::new();
 
     match format {
         "tree" => {
             output.push_str("Directory Structure:\n");
-            output.push_str(&generate_tree(entries)?);
+            output.push_str(&generate_tree(entries, context)?);
         }
         "files" => {
             output.push_str("File Contents:\n");
@@ -23,53 +209,200 @@ pub fn generate_output(entries: &[FileEntry], format: &str) -> Result<String> {
         }
         "both" => {
             output.push_str("Directory Structure:\n");
-            output.push_str(&generate_tree(entries)?);
+            output.push_str(&generate_tree(entries, context)?);
             output.push_str("\nFile Contents:\n");
             output.push_str(&generate_files(entries)?);
         }
+        "xml" => {
+            // The summary lives inside the root element so nothing trails it
+            output.push_str(&generate_xml(entries, context)?);
+            return Ok(output);
+        }
+        "markdown" => {
+            output.push_str(&generate_markdown(entries, context)?);
+            return Ok(output);
+        }
+        "json" => {
+            let json = JsonOutput {
+                files: file_records(entries, context.token_count),
+                summary: summary_record(entries, context),
+            };
+            output.push_str(&serde_json::to_string_pretty(&json)?);
+            output.push('\n');
+            return Ok(output);
+        }
+        "jsonl" => {
+            for record in file_records(entries, context.token_count) {
+                output.push_str(&serde_json::to_string(&record)?);
+                output.push('\n');
+            }
+            output.push_str(&serde_json::to_string(&summary_record(entries, context))?);
+            output.push('\n');
+            return Ok(output);
+        }
         _ => output.push_str("Invalid output format specified\n"),
     }
 
     // Add summary
     output.push_str("\nSummary:\n");
-    output.push_str(&format!("Total files: {}\n", entries.len()));
-    output.push_str(&format!(
-        "Total size: {} bytes\n",
-        entries.iter().map(|e| e.size).sum::<u64>()
-    ));
+    output.push_str(&generate_summary(entries, context));
 
     Ok(output)
 }
 
-pub fn display_token_counts(token_counter: TokenCounter, entries: &[FileEntry]) -> Result<()> {
-    let token_count = token_counter.count_files(entries)?;
+fn generate_summary(entries: &[FileEntry], context: &OutputContext) -> String {
+    let mut summary = format!(
+        "Total files: {}\nTotal size: {} bytes\n",
+        entries.len(),
+        entries.iter().map(|e| e.size).sum::<u64>()
+    );
+    if !context.omitted.is_empty() {
+        summary.push_str(&format!(
+            "Omitted files: {} (over token budget)\n",
+            context.omitted.len()
+        ));
+    }
+    if !context.truncated.is_empty() {
+        summary.push_str(&format!(
+            "Truncated files: {} (kept their first and last lines)\n",
+            context.truncated.len()
+        ));
+    }
+    if !context.skipped.is_empty() {
+        summary.push_str(&format!("{}:\n", skipped_summary(context.skipped)));
+        for file in context.skipped {
+            summary.push_str(&format!(
+                "  {} ({})\n",
+                file.path.display(),
+                file.reason.label()
+            ));
+        }
+    }
+    summary
+}
+
+/// One-line count of skipped files by reason, e.g. "Skipped 3 files (1 binary, 2 too large)"
+pub fn skipped_summary(skipped: &[SkippedFile]) -> String {
+    let mut reasons: Vec<SkipReason> = skipped.iter().map(|file| file.reason).collect();
+    reasons.sort();
+
+    let mut counts: Vec<String> = Vec::new();
+    for chunk in reasons.chunk_by(|a, b| a == b) {
+        counts.push(format!("{} {}", chunk.len(), chunk[0].label()));
+    }
+
+    format!("Skipped {} files ({})", skipped.len(), counts.join(", "))
+}
+
+fn file_records<'a>(
+    entries: &'a [FileEntry],
+    token_count: Option<&TokenCount>,
+) -> Vec<FileRecord<'a>> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| FileRecord {
+            path: entry.path.display().to_string(),
+            size: entry.size,
+            language: entry.language.map(|language| language.name),
+            // count_files produces the breakdown in entry order
+            tokens: token_count.map(|count| count.breakdown[i].1),
+            lines: entry.content.lines().count(),
+            sha256: format!("{:x}", Sha256::digest(entry.content.as_bytes())),
+            content: &entry.content,
+            diff: entry.diff.as_deref(),
+        })
+        .collect()
+}
 
+fn summary_record(entries: &[FileEntry], context: &OutputContext) -> SummaryRecord {
+    SummaryRecord {
+        total_files: entries.len(),
+        total_size: entries.iter().map(|e| e.size).sum(),
+        total_tokens: context.token_count.map(|count| count.total_tokens),
+        omitted: context
+            .omitted
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect(),
+        truncated: context
+            .truncated
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect(),
+        skipped: context
+            .skipped
+            .iter()
+            .map(|file| SkippedRecord {
+                path: file.path.display().to_string(),
+                reason: file.reason,
+            })
+            .collect(),
+        top_files: context
+            .token_count
+            .map(|count| {
+                top_token_files(count)
+                    .into_iter()
+                    .map(|(path, tokens)| TokenRecord {
+                        path: path.display().to_string(),
+                        tokens,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+pub fn display_token_counts(
+    token_count: &TokenCount,
+    strip_savings: &[(StripKind, usize)],
+    gutter_tokens: Option<usize>,
+) -> Result<()> {
     println!("\nToken Count Summary:");
     println!("Total tokens: {}", token_count.total_tokens);
+    for (kind, saved) in strip_savings {
+        println!("Saved by stripping {}: {}", kind.label(), saved);
+    }
+    if let Some(gutter_tokens) = gutter_tokens {
+        println!("Spent on line numbers: {}", gutter_tokens);
+    }
     println!("\nBreakdown by file:");
 
-    // Sorting breakdown
-    let mut breakdown = token_count.breakdown;
-    breakdown.sort_by(|(_, a), (_, b)| b.cmp(a));
-    let top_files = breakdown.iter().take(15);
-
-    for (path, count) in top_files {
+    for (path, count) in top_token_files(token_count) {
         println!("  {}: {}", path.display(), count);
     }
 
     Ok(())
 }
 
-fn generate_tree(entries: &[FileEntry]) -> Result<String> {
+/// The largest files by token count, as shown in the token summary
+fn top_token_files(token_count: &TokenCount) -> Vec<(PathBuf, usize)> {
+    let mut breakdown = token_count.breakdown.clone();
+    breakdown.sort_by(|(_, a), (_, b)| b.cmp(a));
+    breakdown.truncate(15);
+    breakdown
+}
+
+fn generate_tree(entries: &[FileEntry], context: &OutputContext) -> Result<String> {
     let mut output = String::new();
     let mut current_path = vec![];
 
-    // Sort entries by path to ensure consistent output
-    let mut sorted_entries = entries.to_vec();
-    sorted_entries.sort_by(|a, b| a.path.cmp(&b.path));
+    // Sort paths to ensure consistent output, omitted files are listed but marked
+    let mut sorted_paths: Vec<(&Path, &str)> = entries
+        .iter()
+        .map(|e| {
+            let truncated = context.truncated.contains(&e.path);
+            (
+                e.path.as_path(),
+                if truncated { " [truncated]" } else { "" },
+            )
+        })
+        .chain(context.omitted.iter().map(|p| (p.as_path(), " [omitted]")))
+        .collect();
+    sorted_paths.sort_by(|a, b| a.0.cmp(b.0));
 
-    for entry in &sorted_entries {
-        let components: Vec<_> = entry.path.components().collect();
+    for &(path, marker) in &sorted_paths {
+        let components: Vec<_> = path.components().collect();
 
         for (i, component) in components.iter().enumerate() {
             if i >= current_path.len() || component != &current_path[i] {
@@ -77,15 +410,16 @@ fn generate_tree(entries: &[FileEntry]) -> Result<String> {
                 // Always use └── for the last component of a file path
                 if i == components.len() - 1 {
                     output.push_str(&format!(
-                        "{}└── {}\n",
+                        "{}└── {}{}\n",
                         prefix,
-                        component.as_os_str().to_string_lossy()
+                        component.as_os_str().to_string_lossy(),
+                        marker
                     ));
                 } else {
                     // For directories, check if it's the last one at this level
-                    let is_last_dir = sorted_entries
+                    let is_last_dir = sorted_paths
                         .iter()
-                        .filter_map(|e| e.path.components().nth(i))
+                        .filter_map(|(p, _)| p.components().nth(i))
                         .filter(|c| c != component)
                         .count()
                         == 0;
@@ -116,11 +450,315 @@ fn generate_files(entries: &[FileEntry]) -> Result<String> {
         output.push('\n');
         output.push_str(&entry.content);
         output.push('\n');
+        if let Some(diff) = &entry.diff {
+            output.push_str("\nDiff:\n");
+            output.push_str(&"-".repeat(48));
+            output.push('\n');
+            output.push_str(diff);
+        }
+    }
+
+    Ok(output)
+}
+
+/// Splits the output into parts that each stay under `max_tokens`.
+/// Files are only split across parts when a single file exceeds the limit.
+pub fn generate_chunks(
+    entries: &[FileEntry],
+    format: &str,
+    context: &OutputContext,
+    counter: &TokenCounter,
+    max_tokens: usize,
+) -> Result<Vec<String>> {
+    if !matches!(format, "files" | "both") {
+        bail!("--chunk-tokens only supports the files and both output formats");
+    }
+
+    // Leave room for the "Part K of N" header added once the count is known
+    let header_tokens = counter.count_tokens(&chunk_header(999, 999))?;
+    let mut chunker = Chunker {
+        counter,
+        budget: max_tokens.saturating_sub(header_tokens),
+        parts: Vec::new(),
+        current: String::new(),
+        current_tokens: 0,
+        has_sections: false,
+    };
+
+    // The full tree only goes into the first part
+    let mut opening = String::new();
+    if format == "both" {
+        opening.push_str("Directory Structure:\n");
+        opening.push_str(&generate_tree(entries, context)?);
+        opening.push('\n');
+    }
+    opening.push_str("File Contents:\n");
+    chunker.start_part(opening)?;
+
+    for entry in entries {
+        let section = generate_files(std::slice::from_ref(entry))?;
+        let tokens = counter.count_tokens(&section)?;
+        if tokens <= chunker.budget {
+            chunker.push_section(section, tokens)?;
+            continue;
+        }
+
+        // Too big for any part on its own, so split it by lines
+        for piece in split_oversized(entry, counter, chunker.budget)? {
+            let tokens = counter.count_tokens(&piece)?;
+            chunker.push_section(piece, tokens)?;
+        }
+    }
+
+    let summary = format!("\nSummary:\n{}", generate_summary(entries, context));
+    let tokens = counter.count_tokens(&summary)?;
+    chunker.push_section(summary, tokens)?;
+    chunker.parts.push(chunker.current);
+
+    let total = chunker.parts.len();
+    Ok(chunker
+        .parts
+        .into_iter()
+        .enumerate()
+        .map(|(i, part)| format!("{}{}", chunk_header(i + 1, total), part))
+        .collect())
+}
+
+fn chunk_header(part: usize, total: usize) -> String {
+    format!("Part {} of {}\n\n", part, total)
+}
+
+struct Chunker<'a> {
+    counter: &'a TokenCounter,
+    budget: usize,
+    parts: Vec<String>,
+    current: String,
+    current_tokens: usize,
+    has_sections: bool,
+}
+
+impl Chunker<'_> {
+    fn start_part(&mut self, opening: String) -> Result<()> {
+        self.current_tokens = self.counter.count_tokens(&opening)?;
+        self.current = opening;
+        self.has_sections = false;
+        Ok(())
+    }
+
+    fn push_section(&mut self, section: String, tokens: usize) -> Result<()> {
+        if self.has_sections && self.current_tokens + tokens > self.budget {
+            let finished = std::mem::take(&mut self.current);
+            self.parts.push(finished);
+            self.start_part("File Contents (continued):\n".to_string())?;
+        }
+        self.current.push_str(&section);
+        self.current_tokens += tokens;
+        self.has_sections = true;
+        Ok(())
+    }
+}
+
+/// Renders a file as several sections of whole lines, each within `budget` tokens,
+/// followed by its diff if it has one
+fn split_oversized(
+    entry: &FileEntry,
+    counter: &TokenCounter,
+    budget: usize,
+) -> Result<Vec<String>> {
+    let header = |start: usize, end: usize| {
+        format!(
+            "\nFile: {} (lines {}-{})\n{}\n",
+            entry.path.display(),
+            start,
+            end,
+            "=".repeat(48)
+        )
+    };
+    let header_tokens = counter.count_tokens(&header(99999, 99999))?;
+
+    let mut pieces = Vec::new();
+    let mut lines = String::new();
+    let mut tokens = header_tokens;
+    let mut start = 1;
+    for (i, line) in entry.content.lines().enumerate() {
+        let line_tokens = counter.count_tokens(line)? + 1;
+        if !lines.is_empty() && tokens + line_tokens > budget {
+            pieces.push(format!(
+                "{}{}",
+                header(start, i),
+                std::mem::take(&mut lines)
+            ));
+            tokens = header_tokens;
+            start = i + 1;
+        }
+        lines.push_str(line);
+        lines.push('\n');
+        tokens += line_tokens;
+    }
+    if !lines.is_empty() {
+        let end = start + lines.lines().count() - 1;
+        pieces.push(format!("{}{}", header(start, end), lines));
+    }
+    // The diff goes in a piece of its own so it isn't cut mid-hunk
+    if let Some(diff) = &entry.diff {
+        pieces.push(format!(
+            "\nDiff: {}\n{}\n{}",
+            entry.path.display(),
+            "-".repeat(48),
+            diff
+        ));
+    }
+
+    Ok(pieces)
+}
+
+fn generate_xml(entries: &[FileEntry], context: &OutputContext) -> Result<String> {
+    let mut output = String::from("<context>\n");
+
+    output.push_str("<directory_structure>\n");
+    output.push_str(&escape_xml(&generate_tree(entries, context)?));
+    output.push_str("</directory_structure>\n");
+
+    output.push_str("<documents>\n");
+    for (i, entry) in entries.iter().enumerate() {
+        output.push_str(&format!("<document index=\"{}\">\n", i + 1));
+        output.push_str(&format!(
+            "<source>{}</source>\n",
+            escape_xml(&entry.path.display().to_string())
+        ));
+        output.push_str("<document_content>\n");
+        output.push_str(&escape_xml(&entry.content));
+        if !entry.content.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str("</document_content>\n");
+        if let Some(diff) = &entry.diff {
+            output.push_str("<diff>\n");
+            output.push_str(&escape_xml(diff));
+            output.push_str("</diff>\n");
+        }
+        output.push_str("</document>\n");
+    }
+    output.push_str("</documents>\n");
+
+    output.push_str("<summary>\n");
+    output.push_str(&escape_xml(&generate_summary(entries, context)));
+    output.push_str("</summary>\n");
+    output.push_str("</context>\n");
+
+    Ok(output)
+}
+
+fn generate_markdown(entries: &[FileEntry], context: &OutputContext) -> Result<String> {
+    let mut output = String::from("## Directory Structure\n\n");
+    output.push_str("```\n");
+    output.push_str(&generate_tree(entries, context)?);
+    output.push_str("```\n");
+
+    output.push_str("\n## File Contents\n");
+    for entry in entries {
+        let fence = code_fence(&entry.content);
+        output.push_str(&format!("\n### {}\n\n", entry.path.display()));
+        output.push_str(&format!("{}{}\n", fence, fence_info(entry)));
+        output.push_str(&entry.content);
+        if !entry.content.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str(&fence);
+        output.push('\n');
+        if let Some(diff) = &entry.diff {
+            let fence = code_fence(diff);
+            output.push_str(&format!("\n{}diff\n{}{}\n", fence, diff, fence));
+        }
+    }
+
+    output.push_str("\n## Summary\n\n");
+    for line in generate_summary(entries, context).lines() {
+        output.push_str(&format!("- {}\n", line));
     }
 
     Ok(output)
 }
 
+/// Numbers every entry's content in place. Returns the tokens the gutters
+/// added when there's a counter to measure them.
+pub fn number_entries(
+    entries: &mut [FileEntry],
+    counter: Option<&TokenCounter>,
+) -> Result<Option<usize>> {
+    let added = entries
+        .par_iter_mut()
+        .map(|entry| -> Result<usize> {
+            let numbered = number_lines(&entry.content, &entry.origin);
+            let added = match counter {
+                Some(counter) => counter
+                    .count_tokens(&numbered)?
+                    .saturating_sub(counter.count_tokens(&entry.content)?),
+                None => 0,
+            };
+            entry.content = numbered;
+            Ok(added)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(counter.map(|_| added.iter().sum()))
+}
+
+/// Prefixes each line with its line number in the file, right-aligned in a
+/// gutter as wide as the largest one. Lines without one, such as elision
+/// markers, get an empty gutter, and synthetic content isn't numbered.
+pub fn number_lines(content: &str, origin: &LineOrigin) -> String {
+    let numbers: Vec<Option<usize>> = match origin {
+        LineOrigin::Unchanged => (1..=content.lines().count()).map(Some).collect(),
+        LineOrigin::Mapped(numbers) => numbers.clone(),
+        LineOrigin::Synthetic => return content.to_string(),
+    };
+    let last = numbers.iter().flatten().max().copied().unwrap_or(1);
+    let width = last.to_string().len();
+
+    let mut numbered = String::with_capacity(content.len() + content.len() / 4);
+    for (i, line) in content.lines().enumerate() {
+        let number = numbers.get(i).copied().flatten();
+        let gutter = number.map(|n| n.to_string()).unwrap_or_default();
+        if line.is_empty() {
+            numbered.push_str(&format!("{:>width$} |\n", gutter));
+        } else {
+            numbered.push_str(&format!("{:>width$} | {}\n", gutter, line));
+        }
+    }
+    if !content.ends_with('\n') {
+        numbered.pop();
+    }
+    numbered
+}
+
+/// Builds a backtick fence longer than any backtick run inside the content
+fn code_fence(content: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+fn fence_info(entry: &FileEntry) -> String {
+    entry
+        .language
+        .map(|language| language.fence_tag())
+        .unwrap_or_default()
+}
+
+/// Escapes markup characters so file content can never close an enclosing tag
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 pub fn handle_output(content: String, args: &Cli) -> Result<()> {
     // Print to stdout if no other output method is specified
     if args.print {
@@ -144,9 +782,134 @@ pub fn handle_output(content: String, args: &Cli) -> Result<()> {
     Ok(())
 }
 
+/// Wraps output in a prompt template, replacing `{output}` or appending
+/// the output when the template has no placeholder
+pub fn apply_template(template: Option<&str>, parts: &mut [String]) {
+    let Some(template) = template else {
+        return;
+    };
+    let (before, after) = template.split_once("{output}").unwrap_or((template, ""));
+
+    // Chunked output gets the lead-in on the first part and the rest on the last
+    if let Some(first) = parts.first_mut() {
+        first.insert_str(0, before);
+    }
+    if let Some(last) = parts.last_mut() {
+        last.push_str(after);
+    }
+}
+
+/// Tokens a template adds around the output, which chunking has to leave room
+/// for. Every part is budgeted for both sides, since one part may get both.
+pub fn template_tokens(template: Option<&str>, counter: &TokenCounter) -> Result<usize> {
+    let Some(template) = template else {
+        return Ok(0);
+    };
+    let (before, after) = template.split_once("{output}").unwrap_or((template, ""));
+    Ok(counter.count_tokens(before)? + counter.count_tokens(after)?)
+}
+
+/// Hands out chunked output one part at a time
+pub fn handle_chunks(parts: Vec<String>, args: &Cli) -> Result<()> {
+    let total = parts.len();
+
+    if args.print {
+        for part in &parts {
+            println!("{}", part);
+        }
+    }
+
+    // The clipboard only holds one part, so wait for the user between parts
+    if !args.print {
+        for (i, part) in parts.iter().enumerate() {
+            match arboard::Clipboard::new()
+                .and_then(|mut clipboard| clipboard.set_text(part.clone()))
+            {
+                Ok(_) => println!("Part {} of {} copied to clipboard.", i + 1, total),
+                Err(e) => {
+                    eprintln!("Warning: Failed to copy to clipboard: {}. Output will continue with other specified formats.", e);
+                    break;
+                }
+            }
+            if i + 1 < total {
+                println!("Press Enter to copy the next part...");
+                io::stdin().lock().read_line(&mut String::new())?;
+            }
+        }
+    }
+
+    if let Some(file_path) = &args.file {
+        for (i, part) in parts.iter().enumerate() {
+            let mut part_path = file_path.clone().into_os_string();
+            part_path.push(format!(".part{}", i + 1));
+            let part_path = PathBuf::from(part_path);
+            fs::write(&part_path, part)?;
+            println!("Output written to: {}", part_path.display());
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::source_detection::{self, CustomTypes};
+
+    fn test_args() -> Cli {
+        Cli {
+            targets: Vec::new(),
+            path: PathBuf::from("."),
+            file_specs: Vec::new(),
+            include: None,
+            exclude: None,
+            max_size: Some(1000),
+            max_depth: Some(10),
+            output: Some("both".to_string()),
+            file: None,
+            print: false,
+            threads: None,
+            hidden: false,
+            no_ignore: false,
+            no_tokens: true,
+            model: None,
+            tokenizer: Some(crate::cli::TokenizerType::Tiktoken),
+            tokenizer_file: None,
+            interactive: false,
+            profile: None,
+            prompt_template: None,
+            sort: crate::cli::SortOrder::Path,
+            detect: crate::cli::DetectionMode::Both,
+            types: None,
+            type_not: None,
+            type_list: false,
+            include_generated: false,
+            strip: Vec::new(),
+            full_lockfiles: false,
+            line_numbers: false,
+            truncate: false,
+            max_file_tokens: None,
+            head_lines: 100,
+            tail_lines: 20,
+            no_redact: false,
+            redaction: Default::default(),
+            custom_types: Default::default(),
+            skeleton: false,
+            report_skipped: false,
+            max_tokens: None,
+            budget_priority: crate::cli::BudgetPriority::Smallest,
+            priority: None,
+            changed_since: None,
+            staged: false,
+            uncommitted: false,
+            diff: false,
+            chunk_tokens: None,
+        }
+    }
+
+    fn language(path: &str) -> Option<Language> {
+        source_detection::detect_language(Path::new(path), &CustomTypes::default())
+    }
 
     fn create_test_entries() -> Vec<FileEntry> {
         vec![
@@ -154,11 +917,17 @@ mod tests {
                 path: PathBuf::from("src/main.rs"),
                 content: "fn main() {}\n".to_string(),
                 size: 12,
+                diff: None,
+                language: language("src/main.rs"),
+                origin: LineOrigin::Unchanged,
             },
             FileEntry {
                 path: PathBuf::from("src/lib/utils.rs"),
                 content: "pub fn helper() {}\n".to_string(),
                 size: 18,
+                diff: None,
+                language: language("src/lib/utils.rs"),
+                origin: LineOrigin::Unchanged,
             },
         ]
     }
@@ -166,7 +935,7 @@ mod tests {
     #[test]
     fn test_tree_output() {
         let entries = create_test_entries();
-        let tree = generate_tree(&entries).unwrap();
+        let tree = generate_tree(&entries, &OutputContext::default()).unwrap();
         let expected = "└── src/\n  ├── lib/\n    └── utils.rs\n  └── main.rs\n";
         assert_eq!(
             tree, expected,
@@ -174,6 +943,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tree_marks_omitted_files() {
+        let entries = create_test_entries();
+        let omitted = vec![PathBuf::from("src/big.rs")];
+        let truncated = vec![PathBuf::from("src/main.rs")];
+        let context = OutputContext {
+            omitted: &omitted,
+            truncated: &truncated,
+            ..Default::default()
+        };
+        let tree = generate_tree(&entries, &context).unwrap();
+        let expected = "└── src/\n  └── big.rs [omitted]\n  ├── lib/\n    └── utils.rs\n  └── main.rs [truncated]\n";
+        assert_eq!(tree, expected);
+
+        let output = generate_output(&entries, "both", &context).unwrap();
+        assert!(output.contains("Omitted files: 1 (over token budget)"));
+        assert!(output.contains("Truncated files: 1"));
+        assert!(!output.contains("File: src/big.rs"));
+    }
+
+    #[test]
+    fn test_skipped_files_report() {
+        let entries = create_test_entries();
+        let skipped = vec![
+            SkippedFile {
+                path: PathBuf::from("logo.png"),
+                reason: SkipReason::UnknownType,
+            },
+            SkippedFile {
+                path: PathBuf::from("data.bin"),
+                reason: SkipReason::Binary,
+            },
+            SkippedFile {
+                path: PathBuf::from("fixture.json"),
+                reason: SkipReason::TooLarge,
+            },
+            SkippedFile {
+                path: PathBuf::from("target/"),
+                reason: SkipReason::Ignored,
+            },
+        ];
+        assert_eq!(
+            skipped_summary(&skipped),
+            "Skipped 4 files (1 binary, 1 too large, 1 unknown type, 1 ignored)"
+        );
+
+        let context = OutputContext {
+            skipped: &skipped,
+            ..Default::default()
+        };
+        let output = generate_output(&entries, "both", &context).unwrap();
+        assert!(output.contains("Skipped 4 files (1 binary,"));
+        assert!(output.contains("  logo.png (unknown type)\n"));
+        assert!(output.contains("  target/ (ignored)\n"));
+
+        let json = generate_output(&entries, "json", &context).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["summary"]["skipped"][1]["path"], "data.bin");
+        assert_eq!(value["summary"]["skipped"][1]["reason"], "binary");
+        assert_eq!(value["summary"]["skipped"][2]["reason"], "too_large");
+    }
+
     #[test]
     fn test_files_output() {
         let entries = create_test_entries();
@@ -190,32 +1021,354 @@ mod tests {
         assert_eq!(files, expected);
     }
 
+    #[test]
+    fn test_line_numbers() {
+        let content = (1..=10)
+            .map(|i| {
+                if i == 5 {
+                    String::new()
+                } else {
+                    format!("line {}", i)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let numbered = number_lines(&content, &LineOrigin::Unchanged);
+        assert!(numbered.starts_with(" 1 | line 1\n 2 | line 2\n"));
+        assert!(numbered.contains("\n 5 |\n"));
+        assert!(numbered.ends_with("\n10 | line 10"));
+
+        assert_eq!(
+            number_lines("fn main() {}\n", &LineOrigin::Unchanged),
+            "1 | fn main() {}\n"
+        );
+        assert_eq!(number_lines("", &LineOrigin::Unchanged), "");
+        let origin = LineOrigin::Mapped(vec![None, Some(9), Some(10)]);
+        assert_eq!(
+            number_lines(
+                "... [elided 8 lines] ...\nfn nine() {}\nfn ten() {}\n",
+                &origin
+            ),
+            "   | ... [elided 8 lines] ...\n 9 | fn nine() {}\n10 | fn ten() {}\n"
+        );
+        let summary = "# Cargo.lock summary\nserde 1.0.200\n";
+        assert_eq!(number_lines(summary, &LineOrigin::Synthetic), summary);
+
+        let mut entries = create_test_entries();
+        let counter = TokenCounter::new("gpt-4o").unwrap();
+        let added = number_entries(&mut entries, Some(&counter)).unwrap();
+        assert!(added.unwrap() > 0);
+        let xml = generate_xml(&entries, &OutputContext::default()).unwrap();
+        assert!(xml.contains("<document_content>\n1 | fn main() {}\n</document_content>"));
+    }
+
     #[test]
     fn test_generate_output() {
         let entries = create_test_entries();
 
         // Test tree format
-        let tree_output = generate_output(&entries, "tree").unwrap();
+        let tree_output = generate_output(&entries, "tree", &OutputContext::default()).unwrap();
         assert!(tree_output.contains("Directory Structure:"));
         assert!(tree_output.contains("src/"));
         assert!(tree_output.contains("main.rs"));
 
         // Test files format
-        let files_output = generate_output(&entries, "files").unwrap();
+        let files_output = generate_output(&entries, "files", &OutputContext::default()).unwrap();
         assert!(files_output.contains("File Contents:"));
         assert!(files_output.contains("fn main()"));
         assert!(files_output.contains("pub fn helper()"));
 
         // Test both format
-        let both_output = generate_output(&entries, "both").unwrap();
+        let both_output = generate_output(&entries, "both", &OutputContext::default()).unwrap();
         assert!(both_output.contains("Directory Structure:"));
         assert!(both_output.contains("File Contents:"));
 
         // Test invalid format
-        let invalid_output = generate_output(&entries, "invalid").unwrap();
+        let invalid_output =
+            generate_output(&entries, "invalid", &OutputContext::default()).unwrap();
         assert!(invalid_output.contains("Invalid output format"));
     }
 
+    #[test]
+    fn test_xml_output() {
+        let entries = create_test_entries();
+        let xml = generate_output(&entries, "xml", &OutputContext::default()).unwrap();
+
+        assert!(xml.starts_with("<context>\n<directory_structure>\n"));
+        assert!(xml.contains("<document index=\"1\">\n<source>src/main.rs</source>\n"));
+        assert!(xml.contains("<document_content>\nfn main() {}\n</document_content>\n"));
+        assert!(xml.contains("<source>src/lib/utils.rs</source>"));
+        assert!(xml.contains("<summary>\nTotal files: 2\n"));
+        assert!(xml.ends_with("</context>\n"));
+        assert!(!xml.contains("\nSummary:\n"));
+    }
+
+    #[test]
+    fn test_xml_output_escapes_content() {
+        let entries = vec![FileEntry {
+            path: PathBuf::from("evil.xml"),
+            content: "a < b && c\n</document_content></document>\n".to_string(),
+            size: 40,
+            diff: None,
+            language: None,
+            origin: LineOrigin::Unchanged,
+        }];
+        let xml = generate_output(&entries, "xml", &OutputContext::default()).unwrap();
+
+        assert_eq!(xml.matches("</document_content>").count(), 1);
+        assert_eq!(xml.matches("</document>").count(), 1);
+        assert!(xml.contains("a &lt; b &amp;&amp; c\n&lt;/document_content&gt;&lt;/document&gt;\n"));
+    }
+
+    #[test]
+    fn test_markdown_output() {
+        let entries = create_test_entries();
+        let markdown = generate_output(&entries, "markdown", &OutputContext::default()).unwrap();
+
+        assert!(markdown.starts_with("## Directory Structure\n\n```\n└── src/\n"));
+        assert!(markdown.contains("\n### src/main.rs\n\n```rust\nfn main() {}\n```\n"));
+        assert!(markdown.contains("\n### src/lib/utils.rs\n\n```rust\n"));
+        assert!(markdown.contains("## Summary\n\n- Total files: 2\n- Total size: 30 bytes\n"));
+    }
+
+    #[test]
+    fn test_markdown_fence_outgrows_content() {
+        let entries = vec![FileEntry {
+            path: PathBuf::from("README.md"),
+            content: "```rust\nfn main() {}\n```\n".to_string(),
+            size: 24,
+            diff: None,
+            language: language("README.md"),
+            origin: LineOrigin::Unchanged,
+        }];
+        let markdown = generate_output(&entries, "markdown", &OutputContext::default()).unwrap();
+
+        assert!(markdown.contains("\n````markdown\n```rust\nfn main() {}\n```\n````\n"));
+        assert_eq!(code_fence("no ticks"), "```");
+        assert_eq!(code_fence("a ````` b"), "``````");
+    }
+
+    #[test]
+    fn test_json_output() {
+        let entries = create_test_entries();
+        let token_count = TokenCount {
+            total_tokens: 9,
+            breakdown: vec![
+                (PathBuf::from("src/main.rs"), 4),
+                (PathBuf::from("src/lib/utils.rs"), 5),
+            ],
+        };
+        let context = OutputContext {
+            token_count: Some(&token_count),
+            ..Default::default()
+        };
+        let json = generate_output(&entries, "json", &context).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let files = value["files"].as_array().unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["path"], "src/main.rs");
+        assert_eq!(files[0]["size"], 12);
+        assert_eq!(files[0]["language"], "Rust");
+        assert_eq!(files[0]["tokens"], 4);
+        assert_eq!(files[0]["lines"], 1);
+        assert_eq!(files[0]["content"], "fn main() {}\n");
+        assert_eq!(
+            files[0]["sha256"],
+            format!("{:x}", Sha256::digest(b"fn main() {}\n"))
+        );
+
+        let summary = &value["summary"];
+        assert_eq!(summary["total_files"], 2);
+        assert_eq!(summary["total_size"], 30);
+        assert_eq!(summary["total_tokens"], 9);
+        assert_eq!(summary["top_files"][0]["path"], "src/lib/utils.rs");
+        assert_eq!(summary["top_files"][0]["tokens"], 5);
+    }
+
+    #[test]
+    fn test_jsonl_output() {
+        let entries = create_test_entries();
+        let jsonl = generate_output(&entries, "jsonl", &OutputContext::default()).unwrap();
+        let lines: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["type"], "file");
+        assert_eq!(lines[1]["path"], "src/lib/utils.rs");
+        assert!(lines[1]["tokens"].is_null());
+        assert_eq!(lines[2]["type"], "summary");
+        assert_eq!(lines[2]["total_files"], 2);
+        assert!(lines[2]["total_tokens"].is_null());
+    }
+
+    #[test]
+    fn test_chunked_output() -> Result<()> {
+        let counter = TokenCounter::new("gpt-4o")?;
+        let entries: Vec<FileEntry> = (0..6)
+            .map(|i| FileEntry {
+                path: PathBuf::from(format!("src/file{}.rs", i)),
+                content: format!("fn function_{}() {{ let value = {}; }}\n", i, i).repeat(10),
+                size: 0,
+                diff: None,
+                language: None,
+                origin: LineOrigin::Unchanged,
+            })
+            .collect();
+        let section_tokens = counter.count_tokens(&generate_files(&entries[..1])?)?;
+
+        let parts = generate_chunks(
+            &entries,
+            "both",
+            &OutputContext::default(),
+            &counter,
+            section_tokens * 3,
+        )?;
+        let total = parts.len();
+
+        assert!(total > 1);
+        assert!(parts[0].starts_with(&format!("Part 1 of {}\n\nDirectory Structure:\n", total)));
+        assert!(!parts[1].contains("Directory Structure:"));
+        assert!(parts[total - 1].contains("Summary:"));
+        for part in &parts {
+            assert!(counter.count_tokens(part)? <= section_tokens * 3);
+        }
+        for entry in &entries {
+            let header = format!("File: {}\n", entry.path.display());
+            assert_eq!(parts.iter().filter(|p| p.contains(&header)).count(), 1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunked_output_leaves_room_for_template() -> Result<()> {
+        let counter = TokenCounter::new("gpt-4o")?;
+        let entries = vec![FileEntry {
+            path: PathBuf::from("big.rs"),
+            content: "let x = 1;\n".repeat(200),
+            size: 0,
+            diff: None,
+            language: None,
+            origin: LineOrigin::Unchanged,
+        }];
+        let template = Some("Review the following code carefully and list any bugs you find:\n{output}\nReply with a numbered list.");
+
+        let budget = 300 - template_tokens(template, &counter)?;
+        let mut parts = generate_chunks(
+            &entries,
+            "files",
+            &OutputContext::default(),
+            &counter,
+            budget,
+        )?;
+        apply_template(template, &mut parts);
+        for part in &parts {
+            assert!(counter.count_tokens(part)? <= 300);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunked_output_splits_oversized_file() -> Result<()> {
+        let counter = TokenCounter::new("gpt-4o")?;
+        let entries = vec![FileEntry {
+            path: PathBuf::from("big.rs"),
+            content: "let x = 1;\n".repeat(200),
+            size: 0,
+            diff: None,
+            language: None,
+            origin: LineOrigin::Unchanged,
+        }];
+
+        let parts = generate_chunks(&entries, "files", &OutputContext::default(), &counter, 300)?;
+
+        assert!(parts.len() > 1);
+        assert!(parts[0].contains("File: big.rs (lines 1-"));
+        assert!(parts[1].contains("File Contents (continued):"));
+        let lines: usize = parts.iter().map(|p| p.matches("let x = 1;").count()).sum();
+        assert_eq!(lines, 200);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunked_output_keeps_diff_of_split_file() -> Result<()> {
+        let counter = TokenCounter::new("gpt-4o")?;
+        let entries = vec![FileEntry {
+            path: PathBuf::from("big.rs"),
+            content: "let x = 1;\n".repeat(200),
+            size: 0,
+            diff: Some("@@ -1 +1 @@\n-let x = 0;\n+let x = 1;\n".to_string()),
+            language: None,
+            origin: LineOrigin::Unchanged,
+        }];
+
+        let parts = generate_chunks(&entries, "files", &OutputContext::default(), &counter, 300)?;
+
+        let last = parts.last().unwrap();
+        assert!(last.contains("Diff: big.rs\n"));
+        assert!(last.contains("+let x = 1;\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_handle_chunks_writes_parts() {
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let temp_file = temp_dir.path().join("context.txt");
+        let args = Cli {
+            file: Some(temp_file.clone()),
+            print: true,
+            ..test_args()
+        };
+
+        handle_chunks(vec!["one".to_string(), "two".to_string()], &args).unwrap();
+
+        let first = std::fs::read_to_string(temp_dir.path().join("context.txt.part1")).unwrap();
+        let second = std::fs::read_to_string(temp_dir.path().join("context.txt.part2")).unwrap();
+        assert_eq!(first, "one");
+        assert_eq!(second, "two");
+        assert!(!temp_file.exists());
+    }
+
+    #[test]
+    fn test_diff_sections() {
+        let mut entries = create_test_entries();
+        entries[0].diff = Some("@@ -1 +1 @@\n-fn old() {}\n+fn main() {}\n".to_string());
+
+        let files = generate_output(&entries, "files", &OutputContext::default()).unwrap();
+        assert!(files.contains("fn main() {}\n\n\nDiff:\n"));
+        assert!(files.contains("+fn main() {}\n"));
+        assert_eq!(files.matches("Diff:").count(), 1);
+
+        let xml = generate_output(&entries, "xml", &OutputContext::default()).unwrap();
+        assert!(xml.contains("</document_content>\n<diff>\n@@ -1 +1 @@\n"));
+
+        let markdown = generate_output(&entries, "markdown", &OutputContext::default()).unwrap();
+        assert!(markdown.contains("```diff\n@@ -1 +1 @@\n"));
+
+        let json = generate_output(&entries, "jsonl", &OutputContext::default()).unwrap();
+        let first: serde_json::Value = serde_json::from_str(json.lines().next().unwrap()).unwrap();
+        assert!(first["diff"].as_str().unwrap().starts_with("@@"));
+    }
+
+    #[test]
+    fn test_apply_template() {
+        let mut single = vec!["OUTPUT".to_string()];
+        apply_template(Some("Review this:\n{output}\nThanks."), &mut single);
+        assert_eq!(single, vec!["Review this:\nOUTPUT\nThanks."]);
+
+        let mut parts = vec!["ONE".to_string(), "TWO".to_string()];
+        apply_template(Some("Intro\n"), &mut parts);
+        assert_eq!(parts, vec!["Intro\nONE", "TWO"]);
+
+        let mut untouched = vec!["OUTPUT".to_string()];
+        apply_template(None, &mut untouched);
+        assert_eq!(untouched, vec!["OUTPUT"]);
+    }
+
     #[test]
     fn test_handle_output() {
         use tempfile::tempdir;
@@ -225,22 +1378,8 @@ mod tests {
 
         let content = "Test content".to_string();
         let args = Cli {
-            path: PathBuf::from("."),
-            include: None,
-            exclude: None,
-            max_size: Some(1000),
-            max_depth: Some(10),
-            output: Some("both".to_string()),
             file: Some(temp_file.clone()),
-            print: false,
-            threads: None,
-            hidden: false,
-            no_ignore: false,
-            no_tokens: true,
-            model: None,
-            tokenizer: Some(crate::cli::TokenizerType::Tiktoken),
-            tokenizer_file: None,
-            interactive: false,
+            ..test_args()
         };
 
         handle_output(content.clone(), &args).unwrap();
@@ -0,0 +1,459 @@
+use tree_sitter::{Language, Node, Parser, Tree};
+
+use crate::output::Edit;
+use crate::source_detection;
+
+/// Node kinds whose `body` field holds an implementation, per grammar
+const FUNCTION_KINDS: &[&str] = &[
+    // Rust
+    "function_item",
+    // Go
+    "function_declaration",
+    "method_declaration",
+    // JavaScript and TypeScript
+    "generator_function_declaration",
+    "function_expression",
+    "arrow_function",
+    "method_definition",
+    // Python
+    "function_definition",
+];
+
+/// Reduces a file to its outline: imports, type definitions, signatures and
+/// doc comments stay, function bodies become `{ ... }` (`...` in Python).
+/// Returns the edits that elide the bodies, or `None` for languages without
+/// a grammar, which are kept whole.
+pub fn skeleton(language: Option<source_detection::Language>, content: &str) -> Option<Vec<Edit>> {
+    let tree = parse(language, content)?;
+
+    let mut elisions = Vec::new();
+    collect_elisions(tree.root_node(), content, &mut elisions);
+    Some(elisions)
+}
+
+/// Finds the lines of every definition named `symbol`, including the doc
+/// comments, attributes and decorators above it. A qualified name like
+/// `Server::start` or `Store.load` also has to match the enclosing definitions.
+/// Returns `None` for languages without a grammar.
+pub fn symbol_lines(
+    language: Option<source_detection::Language>,
+    content: &str,
+    symbol: &str,
+) -> Option<Vec<(usize, usize)>> {
+    let tree = parse(language, content)?;
+    let mut names: Vec<&str> = symbol
+        .split("::")
+        .flat_map(|part| part.split('.'))
+        .collect();
+    let name = names.pop()?;
+
+    let mut found = Vec::new();
+    collect_definitions(tree.root_node(), content, name, &names, &mut found);
+    Some(found)
+}
+
+fn parse(language: Option<source_detection::Language>, content: &str) -> Option<Tree> {
+    let grammar: Language = match language?.name {
+        "Rust" => tree_sitter_rust::LANGUAGE.into(),
+        "Go" => tree_sitter_go::LANGUAGE.into(),
+        "Python" => tree_sitter_python::LANGUAGE.into(),
+        "JavaScript" => tree_sitter_javascript::LANGUAGE.into(),
+        "TypeScript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        "TSX" => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        _ => return None,
+    };
+
+    let mut parser = Parser::new();
+    parser.set_language(&grammar).ok()?;
+    parser.parse(content, None)
+}
+
+fn collect_definitions(
+    node: Node,
+    content: &str,
+    name: &str,
+    qualifiers: &[&str],
+    found: &mut Vec<(usize, usize)>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if is_definition(child) && definition_name(child, content) == Some(name) {
+            let qualified = enclosing_names(child, content)
+                .collect::<Vec<_>>()
+                .ends_with(qualifiers);
+            if qualified {
+                let definition = outermost_wrapper(child);
+                let start = leading_comments(definition).start_position().row;
+                found.push((start + 1, definition.end_position().row + 1));
+                continue;
+            }
+        }
+        collect_definitions(child, content, name, qualifiers, found);
+    }
+}
+
+fn is_definition(node: Node) -> bool {
+    let kind = node.kind();
+    (kind.ends_with("_item") && kind != "use_item")
+        || kind.ends_with("_definition")
+        || kind.ends_with("_declaration")
+        || matches!(kind, "type_spec" | "variable_declarator" | "method_spec")
+}
+
+/// Rust impls are named by the type they implement
+fn definition_name<'a>(node: Node, content: &'a str) -> Option<&'a str> {
+    let name = node
+        .child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("type"))?;
+    let name = match name.kind() {
+        "generic_type" => name.child_by_field_name("type")?,
+        _ => name,
+    };
+    Some(&content[name.byte_range()])
+}
+
+/// Names of the definitions around a node, outermost first. Go methods are
+/// qualified by their receiver type.
+fn enclosing_names<'a>(node: Node, content: &'a str) -> impl Iterator<Item = &'a str> {
+    let mut names = Vec::new();
+    if let Some(receiver) = node.child_by_field_name("receiver") {
+        let text = &content[receiver.byte_range()];
+        let receiver_type = text
+            .trim_matches(|c| c == '(' || c == ')')
+            .rsplit(|c: char| c.is_whitespace() || c == '*')
+            .next()
+            .unwrap_or_default();
+        names.push(receiver_type);
+    }
+
+    let mut parent = node.parent();
+    while let Some(ancestor) = parent {
+        if is_definition(ancestor) {
+            names.extend(definition_name(ancestor, content));
+        }
+        parent = ancestor.parent();
+    }
+    names.into_iter().rev()
+}
+
+/// Widens a match to the statement that holds it, such as the `const` around
+/// a JavaScript declarator or the `type` around a Go type spec
+fn outermost_wrapper(node: Node) -> Node {
+    let mut node = node;
+    while let Some(parent) = node.parent() {
+        match parent.kind() {
+            "lexical_declaration"
+            | "variable_declaration"
+            | "export_statement"
+            | "decorated_definition"
+            | "type_declaration" => node = parent,
+            _ => break,
+        }
+    }
+    node
+}
+
+/// Extends a definition upward over the comments and attributes directly above it
+fn leading_comments(node: Node) -> Node {
+    let mut start = node;
+    while let Some(previous) = start.prev_sibling() {
+        let attached = matches!(
+            previous.kind(),
+            "line_comment" | "block_comment" | "comment" | "attribute_item" | "decorator"
+        ) && previous.end_position().row + 1 >= start.start_position().row;
+        if !attached {
+            break;
+        }
+        start = previous;
+    }
+    start
+}
+
+/// Finds function bodies in source order. Anything nested in a body goes
+/// with it, so the search doesn't descend into them.
+fn collect_elisions(node: Node, content: &str, elisions: &mut Vec<Edit>) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let body = FUNCTION_KINDS
+            .contains(&child.kind())
+            .then(|| child.child_by_field_name("body"))
+            .flatten();
+        if let Some(elision) = body.and_then(|body| elided_body(body, content)) {
+            elisions.push(elision);
+            continue;
+        }
+        collect_elisions(child, content, elisions);
+    }
+}
+
+fn elided_body(body: Node, content: &str) -> Option<Edit> {
+    match body.kind() {
+        "block" if body.parent()?.kind() == "function_definition" => {
+            Some(python_body(body, content))
+        }
+        // Rust and Go blocks, JavaScript and TypeScript statement blocks
+        "block" | "statement_block" => {
+            Some((body.start_byte(), body.end_byte(), "{ ... }".to_string()))
+        }
+        // Arrow functions returning an expression have nothing to elide
+        _ => None,
+    }
+}
+
+/// Python bodies keep their docstring, followed by `...` at the same indent
+fn python_body(body: Node, content: &str) -> Edit {
+    let docstring = body.named_child(0).filter(|statement| {
+        statement.kind() == "expression_statement"
+            && statement
+                .named_child(0)
+                .is_some_and(|expression| expression.kind() == "string")
+    });
+
+    match docstring {
+        Some(docstring) => {
+            let line_start = content[..body.start_byte()]
+                .rfind('\n')
+                .map_or(0, |i| i + 1);
+            let indent = &content[line_start..body.start_byte()];
+            let indent = if indent.trim().is_empty() {
+                indent
+            } else {
+                " "
+            };
+            // The docstring stays where it is, so it keeps its line numbers
+            (
+                docstring.end_byte(),
+                body.end_byte(),
+                format!("\n{}...", indent),
+            )
+        }
+        None => (body.start_byte(), body.end_byte(), "...".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_detection::CustomTypes;
+    use std::path::Path;
+
+    fn language(file: &str, content: &str) -> Option<source_detection::Language> {
+        source_detection::detect_language_in(Path::new(file), content, &CustomTypes::default())
+    }
+
+    fn outline(file: &str, content: &str) -> String {
+        let elisions =
+            skeleton(language(file, content), content).expect("language should be supported");
+        crate::output::splice(content, &elisions).0
+    }
+
+    #[test]
+    fn test_rust_skeleton() {
+        let content = r#"use std::fmt;
+
+/// A point in space
+pub struct Point {
+    x: i32,
+}
+
+impl Point {
+    /// Makes a point
+    pub fn new(x: i32) -> Self {
+        let y = x * 2;
+        Self { x: y }
+    }
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+fn main() {
+    println!("hi");
+}
+"#;
+        let expected = r#"use std::fmt;
+
+/// A point in space
+pub struct Point {
+    x: i32,
+}
+
+impl Point {
+    /// Makes a point
+    pub fn new(x: i32) -> Self { ... }
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+fn main() { ... }
+"#;
+        assert_eq!(outline("src/point.rs", content), expected);
+    }
+
+    #[test]
+    fn test_python_skeleton() {
+        let content = r#"import os
+
+class Greeter:
+    """Says hello."""
+
+    def greet(self, name: str) -> str:
+        """Greets someone by name."""
+        message = f"Hello, {name}"
+        return message
+
+    def wave(self):
+        print("wave")
+
+def main(): run()
+"#;
+        let expected = r#"import os
+
+class Greeter:
+    """Says hello."""
+
+    def greet(self, name: str) -> str:
+        """Greets someone by name."""
+        ...
+
+    def wave(self):
+        ...
+
+def main(): ...
+"#;
+        assert_eq!(outline("greeter.py", content), expected);
+
+        // Kept lines start where they did, the `...` standing in for a body doesn't
+        let elisions = skeleton(language("greeter.py", content), content).unwrap();
+        let (_, sources) = crate::output::splice(content, &elisions);
+        assert_eq!(sources[5..8], [Some(5), Some(6), None]);
+        assert_eq!(sources[9..11], [Some(10), Some(11)]);
+    }
+
+    #[test]
+    fn test_go_skeleton() {
+        let content = r#"package main
+
+import "fmt"
+
+// Server handles requests
+type Server struct {
+	Port int
+}
+
+func (s *Server) Start() error {
+	fmt.Println(s.Port)
+	return nil
+}
+
+func main() {
+	s := Server{Port: 80}
+	s.Start()
+}
+"#;
+        let expected = r#"package main
+
+import "fmt"
+
+// Server handles requests
+type Server struct {
+	Port int
+}
+
+func (s *Server) Start() error { ... }
+
+func main() { ... }
+"#;
+        assert_eq!(outline("main.go", content), expected);
+    }
+
+    #[test]
+    fn test_typescript_and_javascript_skeleton() {
+        let content = r#"import { api } from "./api";
+
+export interface User {
+  name: string;
+}
+
+export class Store {
+  load(id: number): Promise<User> {
+    return api.get(id);
+  }
+}
+
+export const double = (n: number) => n * 2;
+export const handler = async (event: Event) => {
+  console.log(event);
+};
+"#;
+        let expected = r#"import { api } from "./api";
+
+export interface User {
+  name: string;
+}
+
+export class Store {
+  load(id: number): Promise<User> { ... }
+}
+
+export const double = (n: number) => n * 2;
+export const handler = async (event: Event) => { ... };
+"#;
+        assert_eq!(outline("store.ts", content), expected);
+
+        let content = "function render() {\n  return <App />;\n}\n";
+        assert_eq!(outline("app.jsx", content), "function render() { ... }\n");
+    }
+
+    #[test]
+    fn test_symbol_lines() {
+        let content = r#"/// A point
+#[derive(Debug)]
+pub struct Point {
+    x: i32,
+}
+
+impl Point {
+    /// Makes a point
+    pub fn new(x: i32) -> Self {
+        Self { x }
+    }
+}
+
+impl Shape {
+    fn new() -> Self {
+        Self
+    }
+}
+"#;
+        let lines =
+            |symbol| symbol_lines(language("src/point.rs", content), content, symbol).unwrap();
+        assert_eq!(lines("Point"), vec![(1, 5), (7, 12)]);
+        assert_eq!(lines("new"), vec![(8, 11), (15, 17)]);
+        assert_eq!(lines("Point::new"), vec![(8, 11)]);
+        assert!(lines("missing").is_empty());
+
+        let content = "package main\n\ntype Server struct{}\n\nfunc (s *Server) Start() error {\n\treturn nil\n}\n";
+        let lines = |symbol| symbol_lines(language("main.go", content), content, symbol).unwrap();
+        assert_eq!(lines("Server.Start"), vec![(5, 7)]);
+        assert_eq!(lines("Server"), vec![(3, 3)]);
+
+        let content = "import x from 'x';\n\n// Runs things\nexport const handler = async () => {\n  run();\n};\n";
+        let lines = |symbol| symbol_lines(language("app.ts", content), content, symbol).unwrap();
+        assert_eq!(lines("handler"), vec![(3, 6)]);
+
+        let content =
+            "@cache\ndef load():\n    pass\n\nclass Store:\n    def load(self):\n        pass\n";
+        let lines = |symbol| symbol_lines(language("store.py", content), content, symbol).unwrap();
+        assert_eq!(lines("load"), vec![(1, 3), (6, 7)]);
+        assert_eq!(lines("Store.load"), vec![(6, 7)]);
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        assert!(skeleton(language("notes.md", ""), "# Notes\n").is_none());
+    }
+}
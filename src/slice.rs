@@ -0,0 +1,466 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use rayon::prelude::*;
+
+use crate::cli::Cli;
+use crate::output::{splice, Edit, FileEntry};
+use crate::skeleton;
+use crate::source_detection::Language;
+use crate::tokenizer::TokenCounter;
+
+/// Part of a file to include, from `PATH:START-END` or `PATH#SYMBOL`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSpec {
+    pub path: PathBuf,
+    pub selection: Selection,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selection {
+    Whole,
+    /// 1-based and inclusive, as editors and compilers number lines
+    Lines(usize, usize),
+    /// A definition by name, optionally qualified: `Cli::with_config`
+    Symbol(String),
+}
+
+impl FileSpec {
+    /// Splits off a `:40-90`, `:40` or `#name` suffix. A spec naming a file
+    /// that exists is that file, and anything else is a plain path too, so
+    /// files with `#` or `:` in their names still work.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if Path::new(spec).is_file() {
+            return Ok(Self {
+                path: PathBuf::from(spec),
+                selection: Selection::Whole,
+            });
+        }
+
+        if let Some((path, symbol)) = spec.rsplit_once('#') {
+            let is_symbol = !path.is_empty()
+                && !symbol.is_empty()
+                && symbol
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | ':' | '.'));
+            if is_symbol {
+                return Ok(Self {
+                    path: PathBuf::from(path),
+                    selection: Selection::Symbol(symbol.to_string()),
+                });
+            }
+        }
+
+        if let Some((path, range)) = spec.rsplit_once(':') {
+            let bounds: Option<Vec<usize>> =
+                range.split('-').map(|bound| bound.parse().ok()).collect();
+            match bounds.as_deref() {
+                Some(&[start, end]) if start >= 1 && end >= start => {
+                    return Ok(Self {
+                        path: PathBuf::from(path),
+                        selection: Selection::Lines(start, end),
+                    })
+                }
+                Some(&[line]) if line >= 1 => {
+                    return Ok(Self {
+                        path: PathBuf::from(path),
+                        selection: Selection::Lines(line, line),
+                    })
+                }
+                Some(_) => {
+                    return Err(format!(
+                        "Invalid line range '{}', expected START-END counting from 1",
+                        range
+                    ))
+                }
+                None => {}
+            }
+        }
+
+        Ok(Self {
+            path: PathBuf::from(spec),
+            selection: Selection::Whole,
+        })
+    }
+
+    /// Rebases the path onto the analyzed directory, which it has to be inside
+    pub fn relative_to(self, base: &Path) -> Result<Self> {
+        if !self.path.is_file() {
+            bail!("File '{}' does not exist", self.path.display());
+        }
+        let path = self.path.canonicalize()?;
+        let base = base.canonicalize()?;
+        match path.strip_prefix(&base) {
+            Ok(relative) => Ok(Self {
+                path: relative.to_path_buf(),
+                selection: self.selection,
+            }),
+            Err(_) => bail!(
+                "File '{}' is outside the analyzed directory '{}'",
+                self.path.display(),
+                base.display()
+            ),
+        }
+    }
+}
+
+/// Keeps only the selected parts of a file, returning the edits that put a
+/// marker where lines were left out. `None` when the whole file was asked for.
+pub fn slice(
+    path: &Path,
+    language: Option<Language>,
+    content: &str,
+    selections: &[&Selection],
+) -> Result<Option<Vec<Edit>>> {
+    if selections.contains(&&Selection::Whole) {
+        return Ok(None);
+    }
+
+    let line_count = content.lines().count();
+    let mut ranges = Vec::new();
+    for selection in selections {
+        match selection {
+            Selection::Whole => unreachable!("whole files are returned early"),
+            Selection::Lines(start, _) if *start > line_count => bail!(
+                "Line {} is past the end of '{}' ({} lines)",
+                start,
+                path.display(),
+                line_count
+            ),
+            Selection::Lines(start, end) => ranges.push((*start, (*end).min(line_count))),
+            Selection::Symbol(name) => match skeleton::symbol_lines(language, content, name) {
+                Some(found) if !found.is_empty() => ranges.extend(found),
+                Some(_) => bail!("Symbol '{}' not found in '{}'", name, path.display()),
+                None => bail!(
+                    "Can't find symbols in '{}', use a line range instead",
+                    path.display()
+                ),
+            },
+        }
+    }
+
+    Ok(Some(keep_lines(content, ranges)))
+}
+
+/// Keeps the given 1-based inclusive line ranges, merging any that overlap
+fn keep_lines(content: &str, mut ranges: Vec<(usize, usize)>) -> Vec<Edit> {
+    ranges.sort();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let starts = line_starts(content);
+    let line_count = starts.len() - 1;
+    let mut edits = Vec::new();
+    let mut next = 1;
+    for (start, end) in merged {
+        if start > next {
+            edits.push(elide(
+                &starts,
+                next,
+                start - 1,
+                elision_marker(start - next),
+            ));
+        }
+        next = end + 1;
+    }
+    if next <= line_count {
+        edits.push(elide(
+            &starts,
+            next,
+            line_count,
+            elision_marker(line_count + 1 - next),
+        ));
+    }
+    edits
+}
+
+/// Byte offset of each line's start, plus the end of the content
+fn line_starts(content: &str) -> Vec<usize> {
+    let mut starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    if content.ends_with('\n') || content.is_empty() {
+        starts.pop();
+    }
+    starts.push(content.len());
+    starts
+}
+
+/// Replaces 1-based inclusive lines `first..=last` with a marker line
+fn elide(starts: &[usize], first: usize, last: usize, marker: String) -> Edit {
+    (starts[first - 1], starts[last], marker + "\n")
+}
+
+/// Cuts files over `--max-size` (with `--truncate`) or `--max-file-tokens`
+/// down to their first and last lines, halving both until the file fits.
+/// Returns the paths of the files that were cut.
+pub fn truncate_entries(
+    entries: &mut [FileEntry],
+    args: &Cli,
+    counter: &TokenCounter,
+) -> Result<Vec<PathBuf>> {
+    let max_size = args.max_size.expect("max_size should be set from config");
+
+    let truncated = entries
+        .par_iter_mut()
+        .map(|entry| -> Result<Option<PathBuf>> {
+            // What's left after summaries and skeletons is what has to fit
+            let over_size = args.truncate && entry.content.len() as u64 > max_size;
+            let max_tokens = match args.max_file_tokens {
+                Some(max_tokens) if counter.count_tokens(&entry.content)? > max_tokens => {
+                    Some(max_tokens)
+                }
+                _ => None,
+            };
+            if !over_size && max_tokens.is_none() {
+                return Ok(None);
+            }
+
+            let lines: Vec<&str> = entry.content.lines().collect();
+            let starts = line_starts(&entry.content);
+            let (mut head, mut tail) = (args.head_lines, args.tail_lines);
+            loop {
+                if head + tail < lines.len() {
+                    let elided = &lines[head..lines.len() - tail];
+                    let tokens = counter.count_tokens(&elided.join("\n"))?;
+                    let marker = truncation_marker(elided.len(), tokens);
+                    let edit = elide(&starts, head + 1, lines.len() - tail, marker);
+
+                    // The marker counts toward the limits like any other line
+                    let (truncated, _) = splice(&entry.content, std::slice::from_ref(&edit));
+                    let fits = (!over_size || truncated.len() as u64 <= max_size)
+                        && match max_tokens {
+                            Some(max_tokens) => counter.count_tokens(&truncated)? <= max_tokens,
+                            None => true,
+                        };
+                    if fits || (head == 0 && tail == 0) {
+                        entry.splice(&[edit]);
+                        return Ok(Some(entry.path.clone()));
+                    }
+                }
+                if head == 0 && tail == 0 {
+                    return Ok(None);
+                }
+                (head, tail) = (head / 2, tail / 2);
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(truncated.into_iter().flatten().collect())
+}
+
+pub fn truncation_marker(lines: usize, tokens: usize) -> String {
+    format!(
+        "... [truncated {} / {}] ...",
+        count(lines, "line"),
+        count(tokens, "token")
+    )
+}
+
+pub fn elision_marker(lines: usize) -> String {
+    format!("... [elided {}] ...", count(lines, "line"))
+}
+
+fn count(n: usize, noun: &str) -> String {
+    match n {
+        1 => format!("1 {}", noun),
+        _ => format!("{} {}s", n, noun),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::LineOrigin;
+    use crate::source_detection::{self, CustomTypes};
+    use clap::Parser;
+
+    #[test]
+    fn test_parse_specs() {
+        let spec = |path: &str, selection| FileSpec {
+            path: PathBuf::from(path),
+            selection,
+        };
+        assert_eq!(
+            FileSpec::parse("src/analyzer.rs:40-90").unwrap(),
+            spec("src/analyzer.rs", Selection::Lines(40, 90))
+        );
+        assert_eq!(
+            FileSpec::parse("src/main.rs:7").unwrap(),
+            spec("src/main.rs", Selection::Lines(7, 7))
+        );
+        assert_eq!(
+            FileSpec::parse("src/cli.rs#Cli::with_config").unwrap(),
+            spec(
+                "src/cli.rs",
+                Selection::Symbol("Cli::with_config".to_string())
+            )
+        );
+        assert_eq!(
+            FileSpec::parse("notes/a:b.md").unwrap(),
+            spec("notes/a:b.md", Selection::Whole)
+        );
+        assert!(FileSpec::parse("src/main.rs:90-40").is_err());
+        assert!(FileSpec::parse("src/main.rs:0").is_err());
+
+        // Suffixes only count when the whole spec isn't a file
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes#1.md");
+        std::fs::write(&notes, "# Notes\n").unwrap();
+        let notes = notes.to_str().unwrap();
+        assert_eq!(
+            FileSpec::parse(notes).unwrap(),
+            spec(notes, Selection::Whole)
+        );
+        let symbol = format!("{}#intro", notes);
+        assert_eq!(
+            FileSpec::parse(&symbol).unwrap(),
+            spec(notes, Selection::Symbol("intro".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_slice_lines() {
+        let content: String = (1..=10).map(|i| format!("line {}\n", i)).collect();
+        let path = Path::new("notes.txt");
+
+        let sliced = slice(
+            path,
+            None,
+            &content,
+            &[
+                &Selection::Lines(3, 4),
+                &Selection::Lines(4, 5),
+                &Selection::Lines(9, 20),
+            ],
+        )
+        .unwrap()
+        .unwrap();
+        let (sliced, sources) = splice(&content, &sliced);
+        assert_eq!(
+            sliced,
+            "... [elided 2 lines] ...\nline 3\nline 4\nline 5\n... [elided 3 lines] ...\nline 9\nline 10\n"
+        );
+        assert_eq!(elision_marker(1), "... [elided 1 line] ...");
+        assert_eq!(
+            truncation_marker(1, 1),
+            "... [truncated 1 line / 1 token] ..."
+        );
+        // Markers stand in for lines rather than being one
+        assert_eq!(
+            sources,
+            vec![None, Some(2), Some(3), Some(4), None, Some(8), Some(9)]
+        );
+
+        assert!(slice(
+            path,
+            None,
+            &content,
+            &[&Selection::Whole, &Selection::Lines(1, 2)]
+        )
+        .unwrap()
+        .is_none());
+        assert!(slice(path, None, &content, &[&Selection::Lines(11, 12)]).is_err());
+        assert!(slice(
+            path,
+            None,
+            &content,
+            &[&Selection::Symbol("main".to_string())]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_truncate_entries() {
+        let counter = TokenCounter::new("gpt-4o").unwrap();
+        let content: String = (1..=300).map(|i| format!("row {}\n", i)).collect();
+        let mut entries = vec![
+            FileEntry {
+                path: PathBuf::from("fixtures/big.csv"),
+                size: content.len() as u64,
+                content: content.clone(),
+                diff: None,
+                language: None,
+                origin: LineOrigin::Unchanged,
+            },
+            FileEntry {
+                path: PathBuf::from("src/small.rs"),
+                size: 13,
+                content: "fn main() {}\n".to_string(),
+                diff: None,
+                language: None,
+                origin: LineOrigin::Unchanged,
+            },
+        ];
+        let args = Cli::parse_from([
+            "glimpse",
+            "--max-size=1000",
+            "--truncate",
+            "--head-lines=2",
+            "--tail-lines=1",
+        ]);
+
+        let truncated = truncate_entries(&mut entries, &args, &counter).unwrap();
+        assert_eq!(truncated, vec![PathBuf::from("fixtures/big.csv")]);
+        let elided_tokens = counter
+            .count_tokens(
+                &content
+                    .lines()
+                    .skip(2)
+                    .take(297)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+            .unwrap();
+        assert_eq!(
+            entries[0].content,
+            format!(
+                "row 1\nrow 2\n... [truncated 297 lines / {} tokens] ...\nrow 300\n",
+                elided_tokens
+            )
+        );
+        assert_eq!(
+            entries[0].origin,
+            LineOrigin::Mapped(vec![Some(1), Some(2), None, Some(300)])
+        );
+        assert_eq!(entries[1].content, "fn main() {}\n");
+        assert_eq!(entries[1].origin, LineOrigin::Unchanged);
+
+        // A token cap halves the kept lines until the file fits
+        let mut entries = vec![FileEntry {
+            path: PathBuf::from("fixtures/big.csv"),
+            size: content.len() as u64,
+            content,
+            diff: None,
+            language: None,
+            origin: LineOrigin::Unchanged,
+        }];
+        let args = Cli::parse_from(["glimpse", "--max-size=1000000", "--max-file-tokens=200"]);
+        truncate_entries(&mut entries, &args, &counter).unwrap();
+        assert!(entries[0].content.starts_with("row 1\n"));
+        assert!(counter.count_tokens(&entries[0].content).unwrap() <= 200);
+    }
+
+    #[test]
+    fn test_slice_symbol() {
+        let content =
+            "use std::fmt;\n\n/// Adds\nfn add(a: i32) -> i32 {\n    a + 1\n}\n\nfn main() {}\n";
+        let sliced = slice(
+            Path::new("src/lib.rs"),
+            source_detection::detect_language(Path::new("src/lib.rs"), &CustomTypes::default()),
+            content,
+            &[&Selection::Symbol("add".to_string())],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            splice(content, &sliced).0,
+            "... [elided 2 lines] ...\n/// Adds\nfn add(a: i32) -> i32 {\n    a + 1\n}\n... [elided 2 lines] ...\n"
+        );
+    }
+}
@@ -1,1406 +1,2180 @@
 // src/source_detection.rs
 
+use crate::config::Config;
+use anyhow::{bail, Result};
 use once_cell::sync::Lazy;
-use std::collections::HashSet;
+use regex::RegexSet;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
 use std::path::Path;
+use std::sync::Mutex;
 
-// Using Lazy for zero-cost initialization of our static set
-static SOURCE_EXTENSIONS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
-    let mut set = HashSet::new();
-
-    set.extend(&[
-        "1",  // Roff, Roff Manpage
-        "1in",  // Roff, Roff Manpage
-        "1m",  // Roff, Roff Manpage
-        "1x",  // Roff, Roff Manpage
-        "2",  // Roff, Roff Manpage
-        "2da",  // 2-Dimensional Array
-        "3",  // Roff, Roff Manpage
-        "3in",  // Roff, Roff Manpage
-        "3m",  // Roff, Roff Manpage
-        "3p",  // Roff, Roff Manpage
-        "3pm",  // Roff, Roff Manpage
-        "3qt",  // Roff, Roff Manpage
-        "3x",  // Roff, Roff Manpage
-        "4",  // Roff, Roff Manpage
-        "4DForm",  // JSON
-        "4DProject",  // JSON
-        "4dm",  // 4D
-        "4gl",  // Genero 4gl
-        "4th",  // Forth
-        "5",  // Roff, Roff Manpage
-        "6",  // Roff, Roff Manpage
-        "6pl",  // Raku
-        "6pm",  // Raku
-        "7",  // Roff, Roff Manpage
-        "8",  // Roff, Roff Manpage
-        "8xp",  // TI Program
-        "8xp.txt",  // TI Program
-        "9",  // Roff, Roff Manpage
-        "Dsr",  // Visual Basic 6.0
-        "JSON-tmLanguage",  // JSON
-        "OutJob",  // Altium Designer
-        "PcbDoc",  // Altium Designer
-        "PrjPCB",  // Altium Designer
-        "SchDoc",  // Altium Designer
-        "TextGrid",  // TextGrid
-        "_coffee",  // CoffeeScript
-        "_js",  // JavaScript
-        "_ls",  // LiveScript
-        "a51",  // Assembly
-        "abap",  // ABAP
-        "abnf",  // ABNF
-        "ada",  // Ada
-        "adb",  // Ada
-        "adml",  // XML
-        "admx",  // XML
-        "ado",  // Stata
-        "adoc",  // AsciiDoc
-        "adp",  // Tcl
-        "ads",  // Ada
-        "afm",  // Adobe Font Metrics
-        "agc",  // Apollo Guidance Computer
-        "agda",  // Agda
-        "ahk",  // AutoHotkey
-        "ahkl",  // AutoHotkey
-        "aidl",  // AIDL
-        "aj",  // AspectJ
-        "al",  // AL, Perl
-        "als",  // Alloy
-        "ampl",  // AMPL
-        "angelscript",  // AngelScript
-        "anim",  // Unity3D Asset
-        "ant",  // XML
-        "antlers.html",  // Antlers
-        "antlers.php",  // Antlers
-        "antlers.xml",  // Antlers
-        "apacheconf",  // ApacheConf
-        "apib",  // API Blueprint
-        "apl",  // APL
-        "app",  // Erlang
-        "app.src",  // Erlang
-        "applescript",  // AppleScript
-        "arc",  // Arc
-        "arpa",  // DNS Zone
-        "arr",  // Pyret
-        "as",  // ActionScript, AngelScript
-        "asax",  // ASP.NET
-        "asc",  // AGS Script, AsciiDoc, Public Key
-        "asciidoc",  // AsciiDoc
-        "ascx",  // ASP.NET
-        "asd",  // Common Lisp
-        "asddls",  // ABAP CDS
-        "ash",  // AGS Script
-        "ashx",  // ASP.NET
-        "asl",  // ASL
-        "asm",  // Assembly, Motorola 68K Assembly
-        "asmx",  // ASP.NET
-        "asn",  // ASN.1
-        "asn1",  // ASN.1
-        "asp",  // Classic ASP
-        "aspx",  // ASP.NET
-        "asset",  // Unity3D Asset
-        "astro",  // Astro
-        "asy",  // Asymptote, LTspice Symbol
-        "au3",  // AutoIt
-        "aug",  // Augeas
-        "auk",  // Awk
-        "aux",  // TeX
-        "avdl",  // Avro IDL
-        "avsc",  // JSON
-        "aw",  // PHP
-        "awk",  // Awk
-        "axaml",  // XML
-        "axd",  // ASP.NET
-        "axi",  // NetLinx
-        "axi.erb",  // NetLinx+ERB
-        "axml",  // XML
-        "axs",  // NetLinx
-        "axs.erb",  // NetLinx+ERB
-        "b",  // Brainfuck, Limbo
-        "bal",  // Ballerina
-        "bas",  // B4X, BASIC, FreeBASIC, ...
-        "bash",  // Shell
-        "bat",  // Batchfile
-        "bats",  // Shell
-        "bb",  // BitBake, BlitzBasic, Clojure
-        "bbappend",  // BitBake
-        "bbclass",  // BitBake
-        "bbx",  // TeX
-        "bdf",  // Glyph Bitmap Distribution Format
-        "bdy",  // PLSQL
-        "be",  // Berry
-        "befunge",  // Befunge
-        "bf",  // Beef, Befunge, Brainfuck, ...
-        "bi",  // FreeBASIC
-        "bib",  // BibTeX
-        "bibtex",  // BibTeX
-        "bicep",  // Bicep
-        "bicepparam",  // Bicep
-        "bison",  // Bison
-        "blade",  // Blade
-        "blade.php",  // Blade
-        "bmx",  // BlitzMax
-        "bones",  // JavaScript
-        "boo",  // Boo
-        "boot",  // Clojure
-        "bpl",  // Boogie
-        "bqn",  // BQN
-        "brd",  // Eagle, KiCad Legacy Layout
-        "bro",  // Zeek
-        "brs",  // Brightscript
-        "bs",  // Bikeshed, Bluespec BH, BrighterScript
-        "bsl",  // 1C Enterprise
-        "bsv",  // Bluespec
-        "builder",  // Ruby
-        "builds",  // XML
-        "bzl",  // Starlark
-        "c",  // C
-        "c++",  // C++
-        "c++-objdump",  // Cpp-ObjDump
-        "c++objdump",  // Cpp-ObjDump
-        "c-objdump",  // C-ObjDump
-        "cabal",  // Cabal Config
-        "caddyfile",  // Caddyfile
-        "cairo",  // Cairo, Cairo Zero
-        "cake",  // C#, CoffeeScript
-        "capnp",  // Cap'n Proto
-        "carbon",  // Carbon
-        "cats",  // C
-        "cbl",  // COBOL
-        "cbx",  // TeX
-        "cc",  // C++
-        "ccp",  // COBOL
-        "ccproj",  // XML
-        "ccxml",  // XML
-        "cdc",  // Cadence
-        "cdf",  // Mathematica
-        "cds",  // CAP CDS
-        "ceylon",  // Ceylon
-        "cfc",  // ColdFusion CFC
-        "cfg",  // HAProxy, INI
-        "cfm",  // ColdFusion
-        "cfml",  // ColdFusion
-        "cgi",  // Perl, Python, Shell
-        "cginc",  // HLSL
-        "ch",  // Charity, xBase
-        "chem",  // Pic
-        "chpl",  // Chapel
-        "chs",  // C2hs Haskell
-        "cil",  // CIL
-        "circom",  // Circom
-        "cirru",  // Cirru
-        "cjs",  // JavaScript
-        "cjsx",  // CoffeeScript
-        "ck",  // ChucK
-        "cl",  // Common Lisp, Cool, OpenCL
-        "cl2",  // Clojure
-        "clar",  // Clarity
-        "click",  // Click
-        "clixml",  // XML
-        "clj",  // Clojure
-        "cljc",  // Clojure
-        "cljs",  // Clojure
-        "cljs.hl",  // Clojure
-        "cljscm",  // Clojure
-        "cljx",  // Clojure
-        "clp",  // CLIPS
-        "cls",  // Apex, ObjectScript, OpenEdge ABL, ...
-        "clw",  // Clarion
-        "cmake",  // CMake
-        "cmake.in",  // CMake
-        "cmd",  // Batchfile
-        "cmp",  // Gerber Image
-        "cnc",  // G-code
-        "cnf",  // INI
-        "cob",  // COBOL
-        "cobol",  // COBOL
-        "cocci",  // SmPL
-        "code-snippets",  // JSON with Comments
-        "code-workspace",  // JSON with Comments
-        "coffee",  // CoffeeScript
-        "coffee.md",  // Literate CoffeeScript
-        "com",  // DIGITAL Command Language
-        "command",  // Shell
-        "conll",  // CoNLL-U
-        "conllu",  // CoNLL-U
-        "containerfile",  // Dockerfile
-        "coq",  // Coq
-        "cp",  // C++, Component Pascal
-        "cpp",  // C++
-        "cpp-objdump",  // Cpp-ObjDump
-        "cppm",  // C++
-        "cppobjdump",  // Cpp-ObjDump
-        "cproject",  // XML
-        "cps",  // Component Pascal
-        "cpy",  // COBOL
-        "cql",  // SQL
-        "cr",  // Crystal
-        "crc32",  // Checksums
-        "creole",  // Creole
-        "cs",  // C#, Smalltalk
-        "cs.pp",  // C#
-        "csc",  // GSC
-        "cscfg",  // XML
-        "csd",  // Csound Document
-        "csdef",  // XML
-        "csh",  // Tcsh
-        "cshtml",  // HTML+Razor
-        "csl",  // Kusto, XML
-        "cson",  // CSON
-        "csproj",  // XML
-        "css",  // CSS
-        "csv",  // CSV
-        "csx",  // C#
-        "ct",  // XML
-        "ctl",  // Visual Basic 6.0
-        "ctp",  // PHP
-        "cts",  // TypeScript
-        "cu",  // Cuda
-        "cue",  // CUE, Cue Sheet
-        "cuh",  // Cuda
-        "curry",  // Curry
-        "cw",  // Redcode
-        "cwl",  // Common Workflow Language
-        "cxx",  // C++
-        "cxx-objdump",  // Cpp-ObjDump
-        "cy",  // Cycript
-        "cylc",  // Cylc
-        "cyp",  // Cypher
-        "cypher",  // Cypher
-        "d",  // D, DTrace, Makefile
-        "d-objdump",  // D-ObjDump
-        "d2",  // D2
-        "dae",  // COLLADA
-        "darcspatch",  // Darcs Patch
-        "dart",  // Dart
-        "dats",  // ATS
-        "db2",  // SQLPL
-        "dcl",  // Clean
-        "ddl",  // PLSQL, SQL
-        "decls",  // BlitzBasic
-        "depproj",  // XML
-        "desktop",  // desktop
-        "desktop.in",  // desktop
-        "dfm",  // Pascal
-        "dfy",  // Dafny
-        "dhall",  // Dhall
-        "di",  // D
-        "diff",  // Diff
-        "dircolors",  // dircolors
-        "dita",  // XML
-        "ditamap",  // XML
-        "ditaval",  // XML
-        "djs",  // Dogescript
-        "dll.config",  // XML
-        "dlm",  // IDL
-        "dm",  // DM
-        "do",  // Stata
-        "dockerfile",  // Dockerfile
-        "dof",  // INI
-        "doh",  // Stata
-        "dot",  // Graphviz (DOT)
-        "dotsettings",  // XML
-        "dpatch",  // Darcs Patch
-        "dpr",  // Pascal
-        "druby",  // Mirah
-        "dsc",  // Debian Package Control File, DenizenScript
-        "dsl",  // ASL
-        "dsp",  // Faust, Microsoft Developer Studio Project
-        "dtx",  // TeX
-        "duby",  // Mirah
-        "dwl",  // DataWeave
-        "dyalog",  // APL
-        "dyl",  // Dylan
-        "dylan",  // Dylan
-        "e",  // E, Eiffel, Euphoria
-        "eam.fs",  // Formatted
-        "eb",  // Easybuild
-        "ebnf",  // EBNF
-        "ebuild",  // Gentoo Ebuild
-        "ec",  // eC
-        "ecl",  // ECL, ECLiPSe
-        "eclass",  // Gentoo Eclass
-        "eclxml",  // ECL
-        "ecr",  // HTML+ECR
-        "ect",  // EJS
-        "edc",  // Edje Data Collection
-        "edge",  // Edge
-        "edgeql",  // EdgeQL
-        "editorconfig",  // EditorConfig
-        "edn",  // edn
-        "eex",  // HTML+EEX
-        "eh",  // eC
-        "ejs",  // EJS
-        "ejs.t",  // EJS
-        "el",  // Emacs Lisp
-        "eliom",  // OCaml
-        "eliomi",  // OCaml
-        "elm",  // Elm
-        "elv",  // Elvish
-        "em",  // EmberScript
-        "emacs",  // Emacs Lisp
-        "emacs.desktop",  // Emacs Lisp
-        "emberscript",  // EmberScript
-        "eml",  // E-mail
-        "epj",  // Ecere Projects
-        "eps",  // PostScript
-        "epsi",  // PostScript
-        "eq",  // EQ
-        "erb",  // HTML+ERB
-        "erb.deface",  // HTML+ERB
-        "erl",  // Erlang
-        "es",  // Erlang, JavaScript
-        "es6",  // JavaScript
-        "escript",  // Erlang
-        "esdl",  // EdgeQL
-        "ex",  // Elixir, Euphoria
-        "exs",  // Elixir
-        "eye",  // Ruby
-        "f",  // Filebench WML, Forth, Fortran
-        "f03",  // Fortran Free Form
-        "f08",  // Fortran Free Form
-        "f77",  // Fortran
-        "f90",  // Fortran Free Form
-        "f95",  // Fortran Free Form
-        "factor",  // Factor
-        "fan",  // Fantom
-        "fancypack",  // Fancy
-        "fcgi",  // Lua, PHP, Perl, ...
-        "fea",  // OpenType Feature File
-        "feature",  // Gherkin
-        "filters",  // XML
-        "fir",  // FIRRTL
-        "fish",  // fish
-        "flex",  // JFlex
-        "flf",  // FIGlet Font
-        "flux",  // FLUX
-        "fnc",  // PLSQL
-        "fnl",  // Fennel
-        "for",  // Formatted, Forth, Fortran
-        "forth",  // Forth
-        "fp",  // GLSL
-        "fpp",  // Fortran
-        "fr",  // Forth, Frege, Text
-        "frag",  // GLSL, JavaScript
-        "frg",  // GLSL
-        "frm",  // VBA, Visual Basic 6.0
-        "frt",  // Forth
-        "fs",  // F#, Filterscript, Forth, ...
-        "fsh",  // GLSL
-        "fshader",  // GLSL
-        "fsi",  // F#
-        "fsproj",  // XML
-        "fst",  // F*
-        "fsti",  // F*
-        "fsx",  // F#
-        "fth",  // Forth
-        "ftl",  // Fluent, FreeMarker
-        "fun",  // Standard ML
-        "fut",  // Futhark
-        "fx",  // FLUX, HLSL
-        "fxh",  // HLSL
-        "fxml",  // XML
-        "fy",  // Fancy
-        "g",  // G-code, GAP
-        "g4",  // ANTLR
-        "gaml",  // GAML
-        "gap",  // GAP
-        "gawk",  // Awk
-        "gbl",  // Gerber Image
-        "gbo",  // Gerber Image
-        "gbp",  // Gerber Image
-        "gbr",  // Gerber Image
-        "gbs",  // Gerber Image
-        "gco",  // G-code
-        "gcode",  // G-code
-        "gd",  // GAP, GDScript
-        "gdb",  // GDB
-        "gdbinit",  // GDB
-        "gdnlib",  // Godot Resource
-        "gdns",  // Godot Resource
-        "ged",  // GEDCOM
-        "gemspec",  // Ruby
-        "geo",  // GLSL
-        "geojson",  // JSON
-        "geom",  // GLSL
-        "gf",  // Grammatical Framework
-        "gi",  // GAP
-        "gitignore",  // Ignore List
-        "gjs",  // Glimmer JS
-        "gko",  // Gerber Image
-        "glade",  // XML
-        "gleam",  // Gleam
-        "glf",  // Glyph
-        "glsl",  // GLSL
-        "glslf",  // GLSL
-        "glslv",  // GLSL
-        "gltf",  // JSON
-        "glyphs",  // OpenStep Property List
-        "gmi",  // Gemini
-        "gml",  // Game Maker Language, Gerber Image, Graph Modeling Language, ...
-        "gms",  // GAMS
-        "gmx",  // XML
-        "gn",  // GN
-        "gni",  // GN
-        "gnu",  // Gnuplot
-        "gnuplot",  // Gnuplot
-        "go",  // Go
-        "god",  // Ruby
-        "golo",  // Golo
-        "gp",  // Gnuplot
-        "gpb",  // Gerber Image
-        "gpt",  // Gerber Image
-        "gql",  // GraphQL
-        "grace",  // Grace
-        "gradle",  // Gradle
-        "gradle.kts",  // Gradle Kotlin DSL
-        "graphql",  // GraphQL
-        "graphqls",  // GraphQL
-        "groovy",  // Groovy
-        "grt",  // Groovy
-        "grxml",  // XML
-        "gs",  // GLSL, Genie, Gosu, ...
-        "gsc",  // GSC
-        "gsh",  // GSC
-        "gshader",  // GLSL
-        "gsp",  // Groovy Server Pages
-        "gst",  // Gosu, XML
-        "gsx",  // Gosu
-        "gtl",  // Gerber Image
-        "gto",  // Gerber Image
-        "gtp",  // Gerber Image
-        "gtpl",  // Groovy
-        "gts",  // Gerber Image, Glimmer TS
-        "gv",  // Graphviz (DOT)
-        "gvy",  // Groovy
-        "gyp",  // Python
-        "gypi",  // Python
-        "h",  // C, C++, Objective-C
-        "h++",  // C++
-        "ha",  // Hare
-        "hack",  // Hack
-        "haml",  // Haml
-        "haml.deface",  // Haml
-        "handlebars",  // Handlebars
-        "har",  // JSON
-        "hats",  // ATS
-        "hb",  // Harbour
-        "hbs",  // Handlebars
-        "hc",  // HolyC
-        "hcl",  // HCL
-        "hh",  // C++, Hack
-        "hhi",  // Hack
-        "hic",  // Clojure
-        "hlean",  // Lean
-        "hlsl",  // HLSL
-        "hlsli",  // HLSL
-        "hocon",  // HOCON
-        "hoon",  // hoon
-        "hpp",  // C++
-        "hqf",  // SQF
-        "hql",  // HiveQL
-        "hrl",  // Erlang
-        "hs",  // Haskell
-        "hs-boot",  // Haskell
-        "hsc",  // Haskell
-        "hta",  // HTML
-        "htm",  // HTML
-        "html",  // Ecmarkup, HTML
-        "html.heex",  // HTML+EEX
-        "html.hl",  // HTML
-        "html.leex",  // HTML+EEX
-        "http",  // HTTP
-        "hx",  // Haxe
-        "hxml",  // HXML
-        "hxsl",  // Haxe
-        "hxx",  // C++
-        "hy",  // Hy
-        "hzp",  // XML
-        "i",  // Assembly, Motorola 68K Assembly, SWIG
-        "i3",  // Modula-3
-        "i7x",  // Inform 7
-        "ical",  // iCalendar
-        "ice",  // JSON, Slice
-        "iced",  // CoffeeScript
-        "icl",  // Clean
-        "ics",  // iCalendar
-        "idc",  // C
-        "idr",  // Idris
-        "ig",  // Modula-3
-        "ihlp",  // Stata
-        "ijm",  // ImageJ Macro
-        "ijs",  // J
-        "ik",  // Ioke
-        "ily",  // LilyPond
-        "imba",  // Imba
-        "iml",  // XML
-        "inc",  // Assembly, BitBake, C++, ...
-        "ini",  // INI
-        "ink",  // Ink
-        "inl",  // C++
-        "ino",  // C++
-        "ins",  // TeX
-        "intr",  // Dylan
-        "io",  // Io
-        "iol",  // Jolie
-        "ipf",  // IGOR Pro
-        "ipp",  // C++
-        "ipynb",  // Jupyter Notebook
-        "irclog",  // IRC log
-        "isl",  // Inno Setup
-        "iss",  // Inno Setup
-        "iuml",  // PlantUML
-        "ivy",  // XML
-        "ixx",  // C++
-        "j",  // Jasmin, Objective-J
-        "j2",  // Jinja
-        "jade",  // Pug
-        "jake",  // JavaScript
-        "janet",  // Janet
-        "jav",  // Java
-        "java",  // Java
-        "javascript",  // JavaScript
-        "jbuilder",  // Ruby
-        "jcl",  // JCL
-        "jelly",  // XML
-        "jflex",  // JFlex
-        "jinja",  // Jinja
-        "jinja2",  // Jinja
-        "jison",  // Jison
-        "jisonlex",  // Jison Lex
-        "jl",  // Julia
-        "jq",  // JSONiq, jq
-        "js",  // JavaScript
-        "js.erb",  // JavaScript+ERB
-        "jsb",  // JavaScript
-        "jscad",  // JavaScript
-        "jsfl",  // JavaScript
-        "jsh",  // Java
-        "jslib",  // JavaScript
-        "jsm",  // JavaScript
-        "json",  // JSON, OASv2-json, OASv3-json
-        "json.example",  // JSON
-        "json5",  // JSON5
-        "jsonc",  // JSON with Comments
-        "jsonl",  // JSON
-        "jsonld",  // JSONLD
-        "jsonnet",  // Jsonnet
-        "jsp",  // Java Server Pages
-        "jspre",  // JavaScript
-        "jsproj",  // XML
-        "jss",  // JavaScript
-        "jst",  // EJS
-        "jsx",  // JavaScript
-        "jte",  // Java Template Engine
-        "just",  // Just
-        "kak",  // KakouneScript
-        "kicad_mod",  // KiCad Layout
-        "kicad_pcb",  // KiCad Layout
-        "kicad_sch",  // KiCad Schematic
-        "kicad_wks",  // KiCad Layout
-        "kid",  // Genshi
-        "kit",  // Kit
-        "kml",  // XML
-        "kojo",  // Scala
-        "kql",  // Kusto
-        "krl",  // KRL
-        "ks",  // KerboScript, Kickstart
-        "ksh",  // Shell
-        "ksy",  // Kaitai Struct
-        "kt",  // Kotlin
-        "ktm",  // Kotlin
-        "kts",  // Kotlin
-        "kv",  // kvlang
-        "l",  // Common Lisp, Lex, PicoLisp, ...
-        "lagda",  // Literate Agda
-        "lark",  // Lark
-        "las",  // Lasso
-        "lasso",  // Lasso
-        "lasso8",  // Lasso
-        "lasso9",  // Lasso
-        "latte",  // Latte
-        "launch",  // XML
-        "lbx",  // TeX
-        "ld",  // Linker Script
-        "lds",  // Linker Script
-        "lean",  // Lean, Lean 4
-        "lektorproject",  // INI
-        "less",  // Less
-        "lex",  // Lex
-        "lfe",  // LFE
-        "lgt",  // Logtalk
-        "lhs",  // Literate Haskell
-        "libsonnet",  // Jsonnet
-        "lid",  // Dylan
-        "lidr",  // Idris
-        "ligo",  // LigoLANG
-        "linq",  // C#
-        "liquid",  // Liquid
-        "lisp",  // Common Lisp, NewLisp
-        "litcoffee",  // Literate CoffeeScript
-        "livecodescript",  // LiveCode Script
-        "livemd",  // Markdown
-        "lkml",  // LookML
-        "ll",  // LLVM
-        "lmi",  // Python
-        "logtalk",  // Logtalk
-        "lol",  // LOLCODE
-        "lookml",  // LookML
-        "lpr",  // Pascal
-        "ls",  // LiveScript, LoomScript
-        "lsl",  // LSL
-        "lslp",  // LSL
-        "lsp",  // Common Lisp, NewLisp
-        "ltx",  // TeX
-        "lua",  // Lua
-        "luau",  // Luau
-        "lvclass",  // LabVIEW
-        "lvlib",  // LabVIEW
-        "lvproj",  // LabVIEW
-        "ly",  // LilyPond
-        "m",  // Limbo, M, MATLAB, ...
-        "m2",  // Macaulay2
-        "m3",  // Modula-3
-        "m4",  // M4, M4Sugar
-        "ma",  // Mathematica
-        "mak",  // Makefile
-        "make",  // Makefile
-        "makefile",  // Makefile
-        "mako",  // Mako
-        "man",  // Roff, Roff Manpage
-        "mao",  // Mako
-        "markdown",  // Markdown
-        "marko",  // Marko
-        "mask",  // Mask, Unity3D Asset
-        "mat",  // Unity3D Asset
-        "mata",  // Stata
-        "matah",  // Stata
-        "mathematica",  // Mathematica
-        "matlab",  // MATLAB
-        "mawk",  // Awk
-        "maxhelp",  // Max
-        "maxpat",  // Max
-        "maxproj",  // Max
-        "mbox",  // E-mail
-        "mbt",  // MoonBit
-        "mc",  // M4, Monkey C, Win32 Message File
-        "mcfunction",  // mcfunction
-        "mcmeta",  // JSON
-        "mcr",  // MAXScript
-        "md",  // GCC Machine Description, Markdown
-        "md2",  // Checksums
-        "md4",  // Checksums
-        "md5",  // Checksums
-        "mdoc",  // Roff, Roff Manpage
-        "mdown",  // Markdown
-        "mdpolicy",  // XML
-        "mdwn",  // Markdown
-        "mdx",  // MDX
-        "me",  // Roff
-        "mediawiki",  // Wikitext
-        "mermaid",  // Mermaid
-        "meta",  // Unity3D Asset
-        "metal",  // Metal
-        "mg",  // Modula-3
-        "minid",  // MiniD
-        "mint",  // Mint
-        "mir",  // YAML
-        "mirah",  // Mirah
-        "mjml",  // XML
-        "mjs",  // JavaScript
-        "mk",  // Makefile
-        "mkd",  // Markdown
-        "mkdn",  // Markdown
-        "mkdown",  // Markdown
-        "mkfile",  // Makefile
-        "mkii",  // TeX
-        "mkiv",  // TeX
-        "mkvi",  // TeX
-        "ml",  // OCaml, Standard ML
-        "ml4",  // OCaml
-        "mli",  // OCaml
-        "mligo",  // CameLIGO
-        "mlir",  // MLIR
-        "mll",  // OCaml
-        "mly",  // OCaml
-        "mm",  // Objective-C++, XML
-        "mmd",  // Mermaid
-        "mmk",  // Module Management System
-        "mms",  // Module Management System
-        "mo",  // Modelica, Motoko
-        "mod",  // AMPL, Linux Kernel Module, Modula-2, ...
-        "mojo",  // Mojo, XML
-        "monkey",  // Monkey
-        "monkey2",  // Monkey
-        "moo",  // Mercury, Moocode
-        "moon",  // MoonScript
-        "move",  // Move
-        "mpl",  // JetBrains MPS
-        "mps",  // JetBrains MPS
-        "mq4",  // MQL4
-        "mq5",  // MQL5
-        "mqh",  // MQL4, MQL5
-        "mrc",  // mIRC Script
-        "ms",  // MAXScript, Roff, Unix Assembly
-        "msd",  // JetBrains MPS
-        "msg",  // omnetpp-msg
-        "mspec",  // Ruby
-        "mss",  // CartoCSS
-        "mt",  // Mathematica
-        "mtl",  // Wavefront Material
-        "mtml",  // MTML
-        "mts",  // TypeScript
-        "mu",  // mupad
-        "mud",  // ZIL
-        "muf",  // MUF
-        "mumps",  // M
-        "muse",  // Muse
-        "mustache",  // Mustache
-        "mxml",  // XML
-        "mxt",  // Max
-        "mysql",  // SQL
-        "myt",  // Myghty
-        "n",  // Nemerle, Roff
-        "nanorc",  // nanorc
-        "nas",  // Assembly, Nasal
-        "nasl",  // NASL
-        "nasm",  // Assembly
-        "natvis",  // XML
-        "nawk",  // Awk
-        "nb",  // Mathematica, Text
-        "nbp",  // Mathematica
-        "nc",  // nesC
-        "ncl",  // Gerber Image, NCL, Text, ...
-        "ndproj",  // XML
-        "ne",  // Nearley
-        "nearley",  // Nearley
-        "ned",  // omnetpp-ned
-        "neon",  // NEON
-        "nf",  // Nextflow
-        "nginx",  // Nginx
-        "nginxconf",  // Nginx
-        "ni",  // Inform 7
-        "nim",  // Nim
-        "nim.cfg",  // Nim
-        "nimble",  // Nim
-        "nimrod",  // Nim
-        "nims",  // Nim
-        "ninja",  // Ninja
-        "nit",  // Nit
-        "nix",  // Nix
-        "njk",  // Nunjucks
-        "njs",  // JavaScript
-        "nl",  // NL, NewLisp
-        "nlogo",  // NetLogo
-        "no",  // Text
-        "nomad",  // HCL
-        "nproj",  // XML
-        "nqp",  // Raku
-        "nr",  // Noir, Roff
-        "nse",  // Lua
-        "nsh",  // NSIS
-        "nsi",  // NSIS
-        "nss",  // NWScript
-        "nu",  // Nu, Nushell
-        "numpy",  // NumPy
-        "numpyw",  // NumPy
-        "numsc",  // NumPy
-        "nuspec",  // XML
-        "nut",  // Squirrel
-        "ny",  // Common Lisp
-        "ob2",  // Oberon
-        "obj",  // Wavefront Object
-        "objdump",  // ObjDump
-        "odd",  // XML
-        "odin",  // Object Data Instance Notation, Odin
-        "ol",  // Jolie
-        "omgrofl",  // Omgrofl
-        "ooc",  // ooc
-        "opa",  // Opa
-        "opal",  // Opal
-        "opencl",  // OpenCL
-        "orc",  // Csound
-        "org",  // Org
-        "os",  // 1C Enterprise
-        "osm",  // XML
-        "owl",  // Web Ontology Language
-        "ox",  // Ox
-        "oxh",  // Ox
-        "oxo",  // Ox
-        "oxygene",  // Oxygene
-        "oz",  // Oz
-        "p",  // Gnuplot, OpenEdge ABL
-        "p4",  // P4
-        "p6",  // Raku
-        "p6l",  // Raku
-        "p6m",  // Raku
-        "p8",  // Lua
-        "pac",  // JavaScript
-        "pact",  // Pact
-        "pan",  // Pan
-        "parrot",  // Parrot
-        "pas",  // Pascal
-        "pascal",  // Pascal
-        "pasm",  // Parrot Assembly
-        "pat",  // Max
-        "patch",  // Diff
-        "pb",  // PureBasic
-        "pbi",  // PureBasic
-        "pbt",  // PowerBuilder, Protocol Buffer Text Format
-        "pbtxt",  // Protocol Buffer Text Format
-        "pck",  // PLSQL
-        "pcss",  // PostCSS
-        "pd",  // Pure Data
-        "pd_lua",  // Lua
-        "pddl",  // PDDL
-        "pde",  // Processing
-        "peggy",  // PEG.js
-        "pegjs",  // PEG.js
-        "pep",  // Pep8
-        "per",  // Genero per
-        "perl",  // Perl
-        "pfa",  // PostScript
-        "pgsql",  // PLpgSQL
-        "ph",  // Perl
-        "php",  // Hack, PHP
-        "php3",  // PHP
-        "php4",  // PHP
-        "php5",  // PHP
-        "phps",  // PHP
-        "phpt",  // PHP
-        "phtml",  // HTML+PHP
-        "pic",  // Pic
-        "pig",  // PigLatin
-        "pike",  // Pike
-        "pir",  // Parrot Internal Representation
-        "pkb",  // PLSQL
-        "pkgproj",  // XML
-        "pkl",  // Pickle, Pkl
-        "pks",  // PLSQL
-        "pl",  // Perl, Prolog, Raku
-        "pl6",  // Raku
-        "plantuml",  // PlantUML
-        "plb",  // PLSQL
-        "plist",  // OpenStep Property List, XML Property List
-        "plot",  // Gnuplot
-        "pls",  // PLSQL
-        "plsql",  // PLSQL
-        "plt",  // Gnuplot, Prolog
-        "pluginspec",  // Ruby, XML
-        "plx",  // Perl
-        "pm",  // Perl, Raku, X PixMap
-        "pm6",  // Raku
-        "pml",  // Promela
-        "pmod",  // Pike
-        "po",  // Gettext Catalog
-        "pod",  // Pod, Pod 6
-        "pod6",  // Pod 6
-        "podsl",  // Common Lisp
-        "podspec",  // Ruby
-        "pogo",  // PogoScript
-        "polar",  // Polar
-        "pony",  // Pony
-        "por",  // Portugol
-        "postcss",  // PostCSS
-        "pot",  // Gettext Catalog
-        "pov",  // POV-Ray SDL
-        "pp",  // Pascal, Puppet
-        "pprx",  // REXX
-        "praat",  // Praat
-        "prawn",  // Ruby
-        "prc",  // PLSQL, SQL
-        "prefab",  // Unity3D Asset
-        "prefs",  // INI
-        "prg",  // xBase
-        "pri",  // QMake
-        "prisma",  // Prisma
-        "pro",  // IDL, INI, Proguard, ...
-        "proj",  // XML
-        "prolog",  // Prolog
-        "properties",  // INI, Java Properties
-        "props",  // XML
-        "proto",  // Protocol Buffer
-        "prw",  // xBase
-        "ps",  // PostScript
-        "ps1",  // PowerShell
-        "ps1xml",  // XML
-        "psc",  // Papyrus
-        "psc1",  // XML
-        "psd1",  // PowerShell
-        "psgi",  // Perl
-        "psm1",  // PowerShell
-        "pt",  // XML
-        "pub",  // Public Key
-        "pug",  // Pug
-        "puml",  // PlantUML
-        "purs",  // PureScript
-        "pwn",  // Pawn
-        "pxd",  // Cython
-        "pxi",  // Cython
-        "py",  // Python
-        "py3",  // Python
-        "pyde",  // Python
-        "pyi",  // Python
-        "pyp",  // Python
-        "pyt",  // Python
-        "pytb",  // Python traceback
-        "pyw",  // Python
-        "pyx",  // Cython
-        "q",  // HiveQL, q
-        "qasm",  // OpenQASM
-        "qbs",  // QML
-        "qhelp",  // XML
-        "ql",  // CodeQL
-        "qll",  // CodeQL
-        "qmd",  // RMarkdown
-        "qml",  // QML
-        "qs",  // Q#, Qt Script
-        "r",  // R, Rebol, Rez
-        "r2",  // Rebol
-        "r3",  // Rebol
-        "rabl",  // Ruby
-        "rake",  // Ruby
-        "raku",  // Raku
-        "rakumod",  // Raku
-        "raml",  // RAML
-        "raw",  // Raw token data
-        "razor",  // HTML+Razor
-        "rb",  // Ruby
-        "rbbas",  // REALbasic
-        "rbfrm",  // REALbasic
-        "rbi",  // Ruby
-        "rbmnu",  // REALbasic
-        "rbres",  // REALbasic
-        "rbs",  // RBS
-        "rbtbar",  // REALbasic
-        "rbuild",  // Ruby
-        "rbuistate",  // REALbasic
-        "rbw",  // Ruby
-        "rbx",  // Ruby
-        "rbxs",  // Lua
-        "rchit",  // GLSL
-        "rd",  // R
-        "rdf",  // XML
-        "rdoc",  // RDoc
-        "re",  // C++, Reason
-        "reb",  // Rebol
-        "rebol",  // Rebol
-        "red",  // Red
-        "reds",  // Red
-        "reek",  // YAML
-        "reg",  // Windows Registry Entries
-        "regex",  // Regular Expression
-        "regexp",  // Regular Expression
-        "rego",  // Open Policy Agent
-        "rei",  // Reason
-        "religo",  // ReasonLIGO
-        "res",  // ReScript, XML
-        "resource",  // RobotFramework
-        "rest",  // reStructuredText
-        "rest.txt",  // reStructuredText
-        "resx",  // XML
-        "rex",  // REXX
-        "rexx",  // REXX
-        "rg",  // Rouge
-        "rhtml",  // HTML+ERB
-        "ring",  // Ring
-        "riot",  // Riot
-        "rkt",  // Racket
-        "rktd",  // Racket
-        "rktl",  // Racket
-        "rl",  // Ragel
-        "rmd",  // RMarkdown
-        "rmiss",  // GLSL
-        "rnh",  // RUNOFF
-        "rno",  // RUNOFF, Roff
-        "rnw",  // Sweave
-        "robot",  // RobotFramework
-        "roc",  // Roc
-        "rockspec",  // Lua
-        "roff",  // Roff
-        "ron",  // RON
-        "ronn",  // Markdown
-        "rpgle",  // RPGLE
-        "rpy",  // Python, Ren'Py
-        "rq",  // SPARQL
-        "rs",  // RenderScript, Rust, XML
-        "rs.in",  // Rust
-        "rsc",  // Rascal, RouterOS Script
-        "rsh",  // RenderScript
-        "rss",  // XML
-        "rst",  // reStructuredText
-        "rst.txt",  // reStructuredText
-        "rsx",  // R
-        "rtf",  // Rich Text Format
-        "ru",  // Ruby
-        "ruby",  // Ruby
-        "rviz",  // YAML
-        "s",  // Motorola 68K Assembly, Unix Assembly
-        "sage",  // Sage
-        "sagews",  // Sage
-        "sarif",  // JSON
-        "sas",  // SAS
-        "sass",  // Sass
-        "sats",  // ATS
-        "sbt",  // Scala
-        "sc",  // Scala, SuperCollider
-        "scad",  // OpenSCAD
-        "scala",  // Scala
-        "scaml",  // Scaml
-        "scd",  // Markdown, SuperCollider
-        "sce",  // Scilab
-        "scenic",  // Scenic
-        "sch",  // Eagle, KiCad Schematic, Scheme, ...
-        "sci",  // Scilab
-        "scm",  // Scheme
-        "sco",  // Csound Score
-        "scpt",  // AppleScript
-        "scrbl",  // Racket
-        "scss",  // SCSS
-        "scxml",  // XML
-        "sdc",  // Tcl
-        "sed",  // sed
-        "self",  // Self
-        "service",  // desktop
-        "sexp",  // Common Lisp
-        "sfd",  // Spline Font Database
-        "sfproj",  // XML
-        "sfv",  // Simple File Verification
-        "sh",  // Shell
-        "sh-session",  // ShellSession
-        "sh.in",  // Shell
-        "sha1",  // Checksums
-        "sha2",  // Checksums
-        "sha224",  // Checksums
-        "sha256",  // Checksums
-        "sha256sum",  // Checksums
-        "sha3",  // Checksums
-        "sha384",  // Checksums
-        "sha512",  // Checksums
-        "shader",  // GLSL, ShaderLab
-        "shen",  // Shen
-        "shproj",  // XML
-        "sieve",  // Sieve
-        "sig",  // Standard ML
-        "sj",  // Objective-J
-        "sjs",  // JavaScript
-        "sl",  // Slash
-        "sld",  // Scheme
-        "slim",  // Slim
-        "slint",  // Slint
-        "sln",  // Microsoft Visual Studio Solution
-        "sls",  // SaltStack, Scheme
-        "sma",  // Pawn
-        "smali",  // Smali
-        "smithy",  // Smithy
-        "smk",  // Snakemake
-        "sml",  // Standard ML
-        "smt",  // SMT
-        "smt2",  // SMT
-        "snakefile",  // Snakemake
-        "snap",  // Jest Snapshot
-        "snip",  // Vim Snippet
-        "snippet",  // Vim Snippet
-        "snippets",  // Vim Snippet
-        "sol",  // Gerber Image, Solidity
-        "soy",  // Closure Templates
-        "sp",  // SourcePawn
-        "sparql",  // SPARQL
-        "spc",  // PLSQL
-        "spec",  // Python, RPM Spec, Ruby
-        "spin",  // Propeller Spin
-        "sps",  // Scheme
-        "sqf",  // SQF
-        "sql",  // PLSQL, PLpgSQL, SQL, ...
-        "sqlrpgle",  // RPGLE
-        "sra",  // PowerBuilder
-        "srdf",  // XML
-        "srt",  // SRecode Template, SubRip Text
-        "sru",  // PowerBuilder
-        "srw",  // PowerBuilder
-        "ss",  // Scheme
-        "ssjs",  // JavaScript
-        "sss",  // SugarSS
-        "st",  // Smalltalk, StringTemplate
-        "stTheme",  // XML Property List
-        "stan",  // Stan
-        "star",  // STAR, Starlark
-        "sthlp",  // Stata
-        "stl",  // STL
-        "ston",  // STON
-        "story",  // Gherkin
-        "storyboard",  // XML
-        "sty",  // TeX
-        "styl",  // Stylus
-        "sublime-build",  // JSON with Comments
-        "sublime-color-scheme",  // JSON with Comments
-        "sublime-commands",  // JSON with Comments
-        "sublime-completions",  // JSON with Comments
-        "sublime-keymap",  // JSON with Comments
-        "sublime-macro",  // JSON with Comments
-        "sublime-menu",  // JSON with Comments
-        "sublime-mousemap",  // JSON with Comments
-        "sublime-project",  // JSON with Comments
-        "sublime-settings",  // JSON with Comments
-        "sublime-snippet",  // XML
-        "sublime-syntax",  // YAML
-        "sublime-theme",  // JSON with Comments
-        "sublime-workspace",  // JSON with Comments
-        "sublime_metrics",  // JSON with Comments
-        "sublime_session",  // JSON with Comments
-        "sv",  // SystemVerilog
-        "svelte",  // Svelte
-        "svg",  // SVG
-        "svh",  // SystemVerilog
-        "sw",  // Sway, XML
-        "swift",  // Swift
-        "syntax",  // YAML
-        "t",  // Perl, Raku, Terra, ...
-        "tab",  // SQL
-        "tac",  // Python
-        "tact",  // JSON, Tact
-        "tag",  // Java Server Pages
-        "talon",  // Talon
-        "targets",  // XML
-        "tcc",  // C++
-        "tcl",  // Tcl
-        "tcl.in",  // Tcl
-        "tcsh",  // Tcsh
-        "te",  // SELinux Policy
-        "tea",  // Tea
-        "templ",  // templ
-        "tesc",  // GLSL
-        "tese",  // GLSL
-        "tex",  // TeX
-        "texi",  // Texinfo
-        "texinfo",  // Texinfo
-        "textile",  // Textile
-        "textproto",  // Protocol Buffer Text Format
-        "tf",  // HCL
-        "tfstate",  // JSON
-        "tfstate.backup",  // JSON
-        "tftpl",  // Terraform Template
-        "tfvars",  // HCL
-        "thor",  // Ruby
-        "thrift",  // Thrift
-        "thy",  // Isabelle
-        "tl",  // Type Language
-        "tla",  // TLA
-        "tlv",  // TL-Verilog
-        "tm",  // Tcl
-        "tmCommand",  // XML Property List
-        "tmLanguage",  // XML Property List
-        "tmPreferences",  // XML Property List
-        "tmSnippet",  // XML Property List
-        "tmTheme",  // XML Property List
-        "tmac",  // Roff
-        "tml",  // XML
-        "tmux",  // Shell
-        "toc",  // TeX, World of Warcraft Addon Data
-        "toit",  // Toit
-        "toml",  // TOML
-        "tool",  // Shell
-        "topojson",  // JSON
-        "tpb",  // PLSQL
-        "tpl",  // Smarty
-        "tpp",  // C++
-        "tps",  // PLSQL
-        "tres",  // Godot Resource
-        "trg",  // PLSQL
-        "trigger",  // Apex, Shell
-        "ts",  // TypeScript, XML
-        "tscn",  // Godot Resource
-        "tsp",  // TSPLIB data, TypeSpec
-        "tst",  // GAP, Scilab
-        "tsv",  // TSV
-        "tsx",  // TSX, XML
-        "ttl",  // Turtle
-        "tu",  // Turing
-        "twig",  // Twig
-        "txi",  // Texinfo
-        "txl",  // TXL
-        "txt",  // Adblock Filter List, Text, Vim Help File
-        "txx",  // C++
-        "typ",  // Typst, XML
-        "uc",  // UnrealScript
-        "udf",  // SQL
-        "udo",  // Csound
-        "ui",  // XML
-        "unity",  // Unity3D Asset
-        "uno",  // Uno
-        "upc",  // Unified Parallel C
-        "ur",  // UrWeb
-        "urdf",  // XML
-        "url",  // INI
-        "urs",  // UrWeb
-        "ux",  // XML
-        "v",  // Coq, V, Verilog
-        "vala",  // Vala
-        "vapi",  // Vala
-        "vark",  // Gosu
-        "vb",  // Visual Basic .NET
-        "vba",  // VBA, Vim Script
-        "vbhtml",  // Visual Basic .NET
-        "vbproj",  // XML
-        "vbs",  // VBScript
-        "vcf",  // TSV, vCard
-        "vcl",  // VCL
-        "vcxproj",  // XML
-        "vdf",  // Valve Data Format
-        "veo",  // Verilog
-        "vert",  // GLSL
-        "vh",  // SystemVerilog
-        "vhd",  // VHDL
-        "vhdl",  // VHDL
-        "vhf",  // VHDL
-        "vhi",  // VHDL
-        "vho",  // VHDL
-        "vhost",  // ApacheConf, Nginx
-        "vhs",  // VHDL
-        "vht",  // VHDL
-        "vhw",  // VHDL
-        "vim",  // Vim Script
-        "vimrc",  // Vim Script
-        "viw",  // SQL
-        "vmb",  // Vim Script
-        "volt",  // Volt
-        "vrx",  // GLSL
-        "vs",  // GLSL
-        "vsh",  // GLSL
-        "vshader",  // GLSL
-        "vsixmanifest",  // XML
-        "vssettings",  // XML
-        "vstemplate",  // XML
-        "vtl",  // Velocity Template Language
-        "vtt",  // WebVTT
-        "vue",  // Vue
-        "vw",  // PLSQL
-        "vxml",  // XML
-        "vy",  // Vyper
-        "w",  // CWeb, OpenEdge ABL
-        "wast",  // WebAssembly
-        "wat",  // WebAssembly
-        "watchr",  // Ruby
-        "wdl",  // WDL
-        "webapp",  // JSON
-        "webidl",  // WebIDL
-        "webmanifest",  // JSON
-        "weechatlog",  // IRC log
-        "wgsl",  // WGSL
-        "whiley",  // Whiley
-        "wiki",  // Wikitext
-        "wikitext",  // Wikitext
-        "wisp",  // wisp
-        "wit",  // WebAssembly Interface Type
-        "wixproj",  // XML
-        "wl",  // Mathematica
-        "wlk",  // Wollok
-        "wlt",  // Mathematica
-        "wlua",  // Lua
-        "workbook",  // Markdown
-        "workflow",  // HCL, XML
-        "wren",  // Wren
-        "ws",  // Witcher Script
-        "wsdl",  // XML
-        "wsf",  // XML
-        "wsgi",  // Python
-        "wxi",  // XML
-        "wxl",  // XML
-        "wxs",  // XML
-        "x",  // DirectX 3D File, Linker Script, Logos, ...
-        "x10",  // X10
-        "x3d",  // XML
-        "x68",  // Motorola 68K Assembly
-        "xacro",  // XML
-        "xaml",  // XML
-        "xbm",  // X BitMap
-        "xc",  // XC
-        "xdc",  // Tcl
-        "xht",  // HTML
-        "xhtml",  // HTML
-        "xi",  // Logos
-        "xib",  // XML
-        "xlf",  // XML
-        "xliff",  // XML
-        "xm",  // Logos
-        "xmi",  // XML
-        "xml",  // XML
-        "xml.dist",  // XML
-        "xmp",  // XML
-        "xojo_code",  // Xojo
-        "xojo_menu",  // Xojo
-        "xojo_report",  // Xojo
-        "xojo_script",  // Xojo
-        "xojo_toolbar",  // Xojo
-        "xojo_window",  // Xojo
-        "xpl",  // XProc
-        "xpm",  // X PixMap
-        "xproc",  // XProc
-        "xproj",  // XML
-        "xpy",  // Python
-        "xq",  // XQuery
-        "xql",  // XQuery
-        "xqm",  // XQuery
-        "xquery",  // XQuery
-        "xqy",  // XQuery
-        "xrl",  // Erlang
-        "xs",  // XS
-        "xsd",  // XML
-        "xsh",  // Xonsh
-        "xsjs",  // JavaScript
-        "xsjslib",  // JavaScript
-        "xsl",  // XSLT
-        "xslt",  // XSLT
-        "xsp-config",  // XPages
-        "xsp.metadata",  // XPages
-        "xspec",  // XML
-        "xtend",  // Xtend
-        "xul",  // XML
-        "xzap",  // ZAP
-        "y",  // Yacc
-        "yacc",  // Yacc
-        "yaml",  // MiniYAML, OASv2-yaml, OASv3-yaml, ...
-        "yaml-tmlanguage",  // YAML
-        "yaml.sed",  // YAML
-        "yang",  // YANG
-        "yap",  // Prolog
-        "yar",  // YARA
-        "yara",  // YARA
-        "yasnippet",  // YASnippet
-        "yml",  // MiniYAML, OASv2-yaml, OASv3-yaml, ...
-        "yml.mysql",  // YAML
-        "yrl",  // Erlang
-        "yul",  // Yul
-        "yy",  // JSON, Yacc
-        "yyp",  // JSON
-        "zap",  // ZAP
-        "zcml",  // XML
-        "zeek",  // Zeek
-        "zep",  // Zephir
-        "zig",  // Zig
-        "zig.zon",  // Zig
-        "zil",  // ZIL
-        "zimpl",  // Zimpl
-        "zmpl",  // Zimpl
-        "zone",  // DNS Zone
-        "zpl",  // Zimpl
-        "zs",  // ZenScript
-        "zsh",  // Shell
-        "zsh-theme",  // Shell
+// Using Lazy for zero-cost initialization of our static map
+// Maps each extension to the language(s) it belongs to
+static SOURCE_EXTENSIONS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    let mut map = HashMap::new();
+
+    map.extend([
+        ("1", "Roff, Roff Manpage"),
+        ("1in", "Roff, Roff Manpage"),
+        ("1m", "Roff, Roff Manpage"),
+        ("1x", "Roff, Roff Manpage"),
+        ("2", "Roff, Roff Manpage"),
+        ("2da", "2-Dimensional Array"),
+        ("3", "Roff, Roff Manpage"),
+        ("3in", "Roff, Roff Manpage"),
+        ("3m", "Roff, Roff Manpage"),
+        ("3p", "Roff, Roff Manpage"),
+        ("3pm", "Roff, Roff Manpage"),
+        ("3qt", "Roff, Roff Manpage"),
+        ("3x", "Roff, Roff Manpage"),
+        ("4", "Roff, Roff Manpage"),
+        ("4DForm", "JSON"),
+        ("4DProject", "JSON"),
+        ("4dm", "4D"),
+        ("4gl", "Genero 4gl"),
+        ("4th", "Forth"),
+        ("5", "Roff, Roff Manpage"),
+        ("6", "Roff, Roff Manpage"),
+        ("6pl", "Raku"),
+        ("6pm", "Raku"),
+        ("7", "Roff, Roff Manpage"),
+        ("8", "Roff, Roff Manpage"),
+        ("8xp", "TI Program"),
+        ("8xp.txt", "TI Program"),
+        ("9", "Roff, Roff Manpage"),
+        ("Dsr", "Visual Basic 6.0"),
+        ("JSON-tmLanguage", "JSON"),
+        ("OutJob", "Altium Designer"),
+        ("PcbDoc", "Altium Designer"),
+        ("PrjPCB", "Altium Designer"),
+        ("SchDoc", "Altium Designer"),
+        ("TextGrid", "TextGrid"),
+        ("_coffee", "CoffeeScript"),
+        ("_js", "JavaScript"),
+        ("_ls", "LiveScript"),
+        ("a51", "Assembly"),
+        ("abap", "ABAP"),
+        ("abnf", "ABNF"),
+        ("ada", "Ada"),
+        ("adb", "Ada"),
+        ("adml", "XML"),
+        ("admx", "XML"),
+        ("ado", "Stata"),
+        ("adoc", "AsciiDoc"),
+        ("adp", "Tcl"),
+        ("ads", "Ada"),
+        ("afm", "Adobe Font Metrics"),
+        ("agc", "Apollo Guidance Computer"),
+        ("agda", "Agda"),
+        ("ahk", "AutoHotkey"),
+        ("ahkl", "AutoHotkey"),
+        ("aidl", "AIDL"),
+        ("aj", "AspectJ"),
+        ("al", "AL, Perl"),
+        ("als", "Alloy"),
+        ("ampl", "AMPL"),
+        ("angelscript", "AngelScript"),
+        ("anim", "Unity3D Asset"),
+        ("ant", "XML"),
+        ("antlers.html", "Antlers"),
+        ("antlers.php", "Antlers"),
+        ("antlers.xml", "Antlers"),
+        ("apacheconf", "ApacheConf"),
+        ("apib", "API Blueprint"),
+        ("apl", "APL"),
+        ("app", "Erlang"),
+        ("app.src", "Erlang"),
+        ("applescript", "AppleScript"),
+        ("arc", "Arc"),
+        ("arpa", "DNS Zone"),
+        ("arr", "Pyret"),
+        ("as", "ActionScript, AngelScript"),
+        ("asax", "ASP.NET"),
+        ("asc", "AGS Script, AsciiDoc, Public Key"),
+        ("asciidoc", "AsciiDoc"),
+        ("ascx", "ASP.NET"),
+        ("asd", "Common Lisp"),
+        ("asddls", "ABAP CDS"),
+        ("ash", "AGS Script"),
+        ("ashx", "ASP.NET"),
+        ("asl", "ASL"),
+        ("asm", "Assembly, Motorola 68K Assembly"),
+        ("asmx", "ASP.NET"),
+        ("asn", "ASN.1"),
+        ("asn1", "ASN.1"),
+        ("asp", "Classic ASP"),
+        ("aspx", "ASP.NET"),
+        ("asset", "Unity3D Asset"),
+        ("astro", "Astro"),
+        ("asy", "Asymptote, LTspice Symbol"),
+        ("au3", "AutoIt"),
+        ("aug", "Augeas"),
+        ("auk", "Awk"),
+        ("aux", "TeX"),
+        ("avdl", "Avro IDL"),
+        ("avsc", "JSON"),
+        ("aw", "PHP"),
+        ("awk", "Awk"),
+        ("axaml", "XML"),
+        ("axd", "ASP.NET"),
+        ("axi", "NetLinx"),
+        ("axi.erb", "NetLinx+ERB"),
+        ("axml", "XML"),
+        ("axs", "NetLinx"),
+        ("axs.erb", "NetLinx+ERB"),
+        ("b", "Brainfuck, Limbo"),
+        ("bal", "Ballerina"),
+        ("bas", "B4X, BASIC, FreeBASIC, ..."),
+        ("bash", "Shell"),
+        ("bat", "Batchfile"),
+        ("bats", "Shell"),
+        ("bb", "BitBake, BlitzBasic, Clojure"),
+        ("bbappend", "BitBake"),
+        ("bbclass", "BitBake"),
+        ("bbx", "TeX"),
+        ("bdf", "Glyph Bitmap Distribution Format"),
+        ("bdy", "PLSQL"),
+        ("be", "Berry"),
+        ("befunge", "Befunge"),
+        ("bf", "Beef, Befunge, Brainfuck, ..."),
+        ("bi", "FreeBASIC"),
+        ("bib", "BibTeX"),
+        ("bibtex", "BibTeX"),
+        ("bicep", "Bicep"),
+        ("bicepparam", "Bicep"),
+        ("bison", "Bison"),
+        ("blade", "Blade"),
+        ("blade.php", "Blade"),
+        ("bmx", "BlitzMax"),
+        ("bones", "JavaScript"),
+        ("boo", "Boo"),
+        ("boot", "Clojure"),
+        ("bpl", "Boogie"),
+        ("bqn", "BQN"),
+        ("brd", "Eagle, KiCad Legacy Layout"),
+        ("bro", "Zeek"),
+        ("brs", "Brightscript"),
+        ("bs", "Bikeshed, Bluespec BH, BrighterScript"),
+        ("bsl", "1C Enterprise"),
+        ("bsv", "Bluespec"),
+        ("builder", "Ruby"),
+        ("builds", "XML"),
+        ("bzl", "Starlark"),
+        ("c", "C"),
+        ("c++", "C++"),
+        ("c++-objdump", "Cpp-ObjDump"),
+        ("c++objdump", "Cpp-ObjDump"),
+        ("c-objdump", "C-ObjDump"),
+        ("cabal", "Cabal Config"),
+        ("caddyfile", "Caddyfile"),
+        ("cairo", "Cairo, Cairo Zero"),
+        ("cake", "C#, CoffeeScript"),
+        ("capnp", "Cap'n Proto"),
+        ("carbon", "Carbon"),
+        ("cats", "C"),
+        ("cbl", "COBOL"),
+        ("cbx", "TeX"),
+        ("cc", "C++"),
+        ("ccp", "COBOL"),
+        ("ccproj", "XML"),
+        ("ccxml", "XML"),
+        ("cdc", "Cadence"),
+        ("cdf", "Mathematica"),
+        ("cds", "CAP CDS"),
+        ("ceylon", "Ceylon"),
+        ("cfc", "ColdFusion CFC"),
+        ("cfg", "HAProxy, INI"),
+        ("cfm", "ColdFusion"),
+        ("cfml", "ColdFusion"),
+        ("cgi", "Perl, Python, Shell"),
+        ("cginc", "HLSL"),
+        ("ch", "Charity, xBase"),
+        ("chem", "Pic"),
+        ("chpl", "Chapel"),
+        ("chs", "C2hs Haskell"),
+        ("cil", "CIL"),
+        ("circom", "Circom"),
+        ("cirru", "Cirru"),
+        ("cjs", "JavaScript"),
+        ("cjsx", "CoffeeScript"),
+        ("ck", "ChucK"),
+        ("cl", "Common Lisp, Cool, OpenCL"),
+        ("cl2", "Clojure"),
+        ("clar", "Clarity"),
+        ("click", "Click"),
+        ("clixml", "XML"),
+        ("clj", "Clojure"),
+        ("cljc", "Clojure"),
+        ("cljs", "Clojure"),
+        ("cljs.hl", "Clojure"),
+        ("cljscm", "Clojure"),
+        ("cljx", "Clojure"),
+        ("clp", "CLIPS"),
+        ("cls", "Apex, ObjectScript, OpenEdge ABL, ..."),
+        ("clw", "Clarion"),
+        ("cmake", "CMake"),
+        ("cmake.in", "CMake"),
+        ("cmd", "Batchfile"),
+        ("cmp", "Gerber Image"),
+        ("cnc", "G-code"),
+        ("cnf", "INI"),
+        ("cob", "COBOL"),
+        ("cobol", "COBOL"),
+        ("cocci", "SmPL"),
+        ("code-snippets", "JSON with Comments"),
+        ("code-workspace", "JSON with Comments"),
+        ("coffee", "CoffeeScript"),
+        ("coffee.md", "Literate CoffeeScript"),
+        ("com", "DIGITAL Command Language"),
+        ("command", "Shell"),
+        ("conll", "CoNLL-U"),
+        ("conllu", "CoNLL-U"),
+        ("containerfile", "Dockerfile"),
+        ("coq", "Coq"),
+        ("cp", "C++, Component Pascal"),
+        ("cpp", "C++"),
+        ("cpp-objdump", "Cpp-ObjDump"),
+        ("cppm", "C++"),
+        ("cppobjdump", "Cpp-ObjDump"),
+        ("cproject", "XML"),
+        ("cps", "Component Pascal"),
+        ("cpy", "COBOL"),
+        ("cql", "SQL"),
+        ("cr", "Crystal"),
+        ("crc32", "Checksums"),
+        ("creole", "Creole"),
+        ("cs", "C#, Smalltalk"),
+        ("cs.pp", "C#"),
+        ("csc", "GSC"),
+        ("cscfg", "XML"),
+        ("csd", "Csound Document"),
+        ("csdef", "XML"),
+        ("csh", "Tcsh"),
+        ("cshtml", "HTML+Razor"),
+        ("csl", "Kusto, XML"),
+        ("cson", "CSON"),
+        ("csproj", "XML"),
+        ("css", "CSS"),
+        ("csv", "CSV"),
+        ("csx", "C#"),
+        ("ct", "XML"),
+        ("ctl", "Visual Basic 6.0"),
+        ("ctp", "PHP"),
+        ("cts", "TypeScript"),
+        ("cu", "Cuda"),
+        ("cue", "CUE, Cue Sheet"),
+        ("cuh", "Cuda"),
+        ("curry", "Curry"),
+        ("cw", "Redcode"),
+        ("cwl", "Common Workflow Language"),
+        ("cxx", "C++"),
+        ("cxx-objdump", "Cpp-ObjDump"),
+        ("cy", "Cycript"),
+        ("cylc", "Cylc"),
+        ("cyp", "Cypher"),
+        ("cypher", "Cypher"),
+        ("d", "D, DTrace, Makefile"),
+        ("d-objdump", "D-ObjDump"),
+        ("d2", "D2"),
+        ("dae", "COLLADA"),
+        ("darcspatch", "Darcs Patch"),
+        ("dart", "Dart"),
+        ("dats", "ATS"),
+        ("db2", "SQLPL"),
+        ("dcl", "Clean"),
+        ("ddl", "PLSQL, SQL"),
+        ("decls", "BlitzBasic"),
+        ("depproj", "XML"),
+        ("desktop", "desktop"),
+        ("desktop.in", "desktop"),
+        ("dfm", "Pascal"),
+        ("dfy", "Dafny"),
+        ("dhall", "Dhall"),
+        ("di", "D"),
+        ("diff", "Diff"),
+        ("dircolors", "dircolors"),
+        ("dita", "XML"),
+        ("ditamap", "XML"),
+        ("ditaval", "XML"),
+        ("djs", "Dogescript"),
+        ("dll.config", "XML"),
+        ("dlm", "IDL"),
+        ("dm", "DM"),
+        ("do", "Stata"),
+        ("dockerfile", "Dockerfile"),
+        ("dof", "INI"),
+        ("doh", "Stata"),
+        ("dot", "Graphviz (DOT)"),
+        ("dotsettings", "XML"),
+        ("dpatch", "Darcs Patch"),
+        ("dpr", "Pascal"),
+        ("druby", "Mirah"),
+        ("dsc", "Debian Package Control File, DenizenScript"),
+        ("dsl", "ASL"),
+        ("dsp", "Faust, Microsoft Developer Studio Project"),
+        ("dtx", "TeX"),
+        ("duby", "Mirah"),
+        ("dwl", "DataWeave"),
+        ("dyalog", "APL"),
+        ("dyl", "Dylan"),
+        ("dylan", "Dylan"),
+        ("e", "E, Eiffel, Euphoria"),
+        ("eam.fs", "Formatted"),
+        ("eb", "Easybuild"),
+        ("ebnf", "EBNF"),
+        ("ebuild", "Gentoo Ebuild"),
+        ("ec", "eC"),
+        ("ecl", "ECL, ECLiPSe"),
+        ("eclass", "Gentoo Eclass"),
+        ("eclxml", "ECL"),
+        ("ecr", "HTML+ECR"),
+        ("ect", "EJS"),
+        ("edc", "Edje Data Collection"),
+        ("edge", "Edge"),
+        ("edgeql", "EdgeQL"),
+        ("editorconfig", "EditorConfig"),
+        ("edn", "edn"),
+        ("eex", "HTML+EEX"),
+        ("eh", "eC"),
+        ("ejs", "EJS"),
+        ("ejs.t", "EJS"),
+        ("el", "Emacs Lisp"),
+        ("eliom", "OCaml"),
+        ("eliomi", "OCaml"),
+        ("elm", "Elm"),
+        ("elv", "Elvish"),
+        ("em", "EmberScript"),
+        ("emacs", "Emacs Lisp"),
+        ("emacs.desktop", "Emacs Lisp"),
+        ("emberscript", "EmberScript"),
+        ("eml", "E-mail"),
+        ("epj", "Ecere Projects"),
+        ("eps", "PostScript"),
+        ("epsi", "PostScript"),
+        ("eq", "EQ"),
+        ("erb", "HTML+ERB"),
+        ("erb.deface", "HTML+ERB"),
+        ("erl", "Erlang"),
+        ("es", "Erlang, JavaScript"),
+        ("es6", "JavaScript"),
+        ("escript", "Erlang"),
+        ("esdl", "EdgeQL"),
+        ("ex", "Elixir, Euphoria"),
+        ("exs", "Elixir"),
+        ("eye", "Ruby"),
+        ("f", "Filebench WML, Forth, Fortran"),
+        ("f03", "Fortran Free Form"),
+        ("f08", "Fortran Free Form"),
+        ("f77", "Fortran"),
+        ("f90", "Fortran Free Form"),
+        ("f95", "Fortran Free Form"),
+        ("factor", "Factor"),
+        ("fan", "Fantom"),
+        ("fancypack", "Fancy"),
+        ("fcgi", "Lua, PHP, Perl, ..."),
+        ("fea", "OpenType Feature File"),
+        ("feature", "Gherkin"),
+        ("filters", "XML"),
+        ("fir", "FIRRTL"),
+        ("fish", "fish"),
+        ("flex", "JFlex"),
+        ("flf", "FIGlet Font"),
+        ("flux", "FLUX"),
+        ("fnc", "PLSQL"),
+        ("fnl", "Fennel"),
+        ("for", "Formatted, Forth, Fortran"),
+        ("forth", "Forth"),
+        ("fp", "GLSL"),
+        ("fpp", "Fortran"),
+        ("fr", "Forth, Frege, Text"),
+        ("frag", "GLSL, JavaScript"),
+        ("frg", "GLSL"),
+        ("frm", "VBA, Visual Basic 6.0"),
+        ("frt", "Forth"),
+        ("fs", "F#, Filterscript, Forth, ..."),
+        ("fsh", "GLSL"),
+        ("fshader", "GLSL"),
+        ("fsi", "F#"),
+        ("fsproj", "XML"),
+        ("fst", "F*"),
+        ("fsti", "F*"),
+        ("fsx", "F#"),
+        ("fth", "Forth"),
+        ("ftl", "Fluent, FreeMarker"),
+        ("fun", "Standard ML"),
+        ("fut", "Futhark"),
+        ("fx", "FLUX, HLSL"),
+        ("fxh", "HLSL"),
+        ("fxml", "XML"),
+        ("fy", "Fancy"),
+        ("g", "G-code, GAP"),
+        ("g4", "ANTLR"),
+        ("gaml", "GAML"),
+        ("gap", "GAP"),
+        ("gawk", "Awk"),
+        ("gbl", "Gerber Image"),
+        ("gbo", "Gerber Image"),
+        ("gbp", "Gerber Image"),
+        ("gbr", "Gerber Image"),
+        ("gbs", "Gerber Image"),
+        ("gco", "G-code"),
+        ("gcode", "G-code"),
+        ("gd", "GAP, GDScript"),
+        ("gdb", "GDB"),
+        ("gdbinit", "GDB"),
+        ("gdnlib", "Godot Resource"),
+        ("gdns", "Godot Resource"),
+        ("ged", "GEDCOM"),
+        ("gemspec", "Ruby"),
+        ("geo", "GLSL"),
+        ("geojson", "JSON"),
+        ("geom", "GLSL"),
+        ("gf", "Grammatical Framework"),
+        ("gi", "GAP"),
+        ("gitignore", "Ignore List"),
+        ("gjs", "Glimmer JS"),
+        ("gko", "Gerber Image"),
+        ("glade", "XML"),
+        ("gleam", "Gleam"),
+        ("glf", "Glyph"),
+        ("glsl", "GLSL"),
+        ("glslf", "GLSL"),
+        ("glslv", "GLSL"),
+        ("gltf", "JSON"),
+        ("glyphs", "OpenStep Property List"),
+        ("gmi", "Gemini"),
+        (
+            "gml",
+            "Game Maker Language, Gerber Image, Graph Modeling Language, ...",
+        ),
+        ("gms", "GAMS"),
+        ("gmx", "XML"),
+        ("gn", "GN"),
+        ("gni", "GN"),
+        ("gnu", "Gnuplot"),
+        ("gnuplot", "Gnuplot"),
+        ("go", "Go"),
+        ("god", "Ruby"),
+        ("golo", "Golo"),
+        ("gp", "Gnuplot"),
+        ("gpb", "Gerber Image"),
+        ("gpt", "Gerber Image"),
+        ("gql", "GraphQL"),
+        ("grace", "Grace"),
+        ("gradle", "Gradle"),
+        ("gradle.kts", "Gradle Kotlin DSL"),
+        ("graphql", "GraphQL"),
+        ("graphqls", "GraphQL"),
+        ("groovy", "Groovy"),
+        ("grt", "Groovy"),
+        ("grxml", "XML"),
+        ("gs", "GLSL, Genie, Gosu, ..."),
+        ("gsc", "GSC"),
+        ("gsh", "GSC"),
+        ("gshader", "GLSL"),
+        ("gsp", "Groovy Server Pages"),
+        ("gst", "Gosu, XML"),
+        ("gsx", "Gosu"),
+        ("gtl", "Gerber Image"),
+        ("gto", "Gerber Image"),
+        ("gtp", "Gerber Image"),
+        ("gtpl", "Groovy"),
+        ("gts", "Gerber Image, Glimmer TS"),
+        ("gv", "Graphviz (DOT)"),
+        ("gvy", "Groovy"),
+        ("gyp", "Python"),
+        ("gypi", "Python"),
+        ("h", "C, C++, Objective-C"),
+        ("h++", "C++"),
+        ("ha", "Hare"),
+        ("hack", "Hack"),
+        ("haml", "Haml"),
+        ("haml.deface", "Haml"),
+        ("handlebars", "Handlebars"),
+        ("har", "JSON"),
+        ("hats", "ATS"),
+        ("hb", "Harbour"),
+        ("hbs", "Handlebars"),
+        ("hc", "HolyC"),
+        ("hcl", "HCL"),
+        ("hh", "C++, Hack"),
+        ("hhi", "Hack"),
+        ("hic", "Clojure"),
+        ("hlean", "Lean"),
+        ("hlsl", "HLSL"),
+        ("hlsli", "HLSL"),
+        ("hocon", "HOCON"),
+        ("hoon", "hoon"),
+        ("hpp", "C++"),
+        ("hqf", "SQF"),
+        ("hql", "HiveQL"),
+        ("hrl", "Erlang"),
+        ("hs", "Haskell"),
+        ("hs-boot", "Haskell"),
+        ("hsc", "Haskell"),
+        ("hta", "HTML"),
+        ("htm", "HTML"),
+        ("html", "Ecmarkup, HTML"),
+        ("html.heex", "HTML+EEX"),
+        ("html.hl", "HTML"),
+        ("html.leex", "HTML+EEX"),
+        ("http", "HTTP"),
+        ("hx", "Haxe"),
+        ("hxml", "HXML"),
+        ("hxsl", "Haxe"),
+        ("hxx", "C++"),
+        ("hy", "Hy"),
+        ("hzp", "XML"),
+        ("i", "Assembly, Motorola 68K Assembly, SWIG"),
+        ("i3", "Modula-3"),
+        ("i7x", "Inform 7"),
+        ("ical", "iCalendar"),
+        ("ice", "JSON, Slice"),
+        ("iced", "CoffeeScript"),
+        ("icl", "Clean"),
+        ("ics", "iCalendar"),
+        ("idc", "C"),
+        ("idr", "Idris"),
+        ("ig", "Modula-3"),
+        ("ihlp", "Stata"),
+        ("ijm", "ImageJ Macro"),
+        ("ijs", "J"),
+        ("ik", "Ioke"),
+        ("ily", "LilyPond"),
+        ("imba", "Imba"),
+        ("iml", "XML"),
+        ("inc", "Assembly, BitBake, C++, ..."),
+        ("ini", "INI"),
+        ("ink", "Ink"),
+        ("inl", "C++"),
+        ("ino", "C++"),
+        ("ins", "TeX"),
+        ("intr", "Dylan"),
+        ("io", "Io"),
+        ("iol", "Jolie"),
+        ("ipf", "IGOR Pro"),
+        ("ipp", "C++"),
+        ("ipynb", "Jupyter Notebook"),
+        ("irclog", "IRC log"),
+        ("isl", "Inno Setup"),
+        ("iss", "Inno Setup"),
+        ("iuml", "PlantUML"),
+        ("ivy", "XML"),
+        ("ixx", "C++"),
+        ("j", "Jasmin, Objective-J"),
+        ("j2", "Jinja"),
+        ("jade", "Pug"),
+        ("jake", "JavaScript"),
+        ("janet", "Janet"),
+        ("jav", "Java"),
+        ("java", "Java"),
+        ("javascript", "JavaScript"),
+        ("jbuilder", "Ruby"),
+        ("jcl", "JCL"),
+        ("jelly", "XML"),
+        ("jflex", "JFlex"),
+        ("jinja", "Jinja"),
+        ("jinja2", "Jinja"),
+        ("jison", "Jison"),
+        ("jisonlex", "Jison Lex"),
+        ("jl", "Julia"),
+        ("jq", "JSONiq, jq"),
+        ("js", "JavaScript"),
+        ("js.erb", "JavaScript+ERB"),
+        ("jsb", "JavaScript"),
+        ("jscad", "JavaScript"),
+        ("jsfl", "JavaScript"),
+        ("jsh", "Java"),
+        ("jslib", "JavaScript"),
+        ("jsm", "JavaScript"),
+        ("json", "JSON, OASv2-json, OASv3-json"),
+        ("json.example", "JSON"),
+        ("json5", "JSON5"),
+        ("jsonc", "JSON with Comments"),
+        ("jsonl", "JSON"),
+        ("jsonld", "JSONLD"),
+        ("jsonnet", "Jsonnet"),
+        ("jsp", "Java Server Pages"),
+        ("jspre", "JavaScript"),
+        ("jsproj", "XML"),
+        ("jss", "JavaScript"),
+        ("jst", "EJS"),
+        ("jsx", "JavaScript"),
+        ("jte", "Java Template Engine"),
+        ("just", "Just"),
+        ("kak", "KakouneScript"),
+        ("kicad_mod", "KiCad Layout"),
+        ("kicad_pcb", "KiCad Layout"),
+        ("kicad_sch", "KiCad Schematic"),
+        ("kicad_wks", "KiCad Layout"),
+        ("kid", "Genshi"),
+        ("kit", "Kit"),
+        ("kml", "XML"),
+        ("kojo", "Scala"),
+        ("kql", "Kusto"),
+        ("krl", "KRL"),
+        ("ks", "KerboScript, Kickstart"),
+        ("ksh", "Shell"),
+        ("ksy", "Kaitai Struct"),
+        ("kt", "Kotlin"),
+        ("ktm", "Kotlin"),
+        ("kts", "Kotlin"),
+        ("kv", "kvlang"),
+        ("l", "Common Lisp, Lex, PicoLisp, ..."),
+        ("lagda", "Literate Agda"),
+        ("lark", "Lark"),
+        ("las", "Lasso"),
+        ("lasso", "Lasso"),
+        ("lasso8", "Lasso"),
+        ("lasso9", "Lasso"),
+        ("latte", "Latte"),
+        ("launch", "XML"),
+        ("lbx", "TeX"),
+        ("ld", "Linker Script"),
+        ("lds", "Linker Script"),
+        ("lean", "Lean, Lean 4"),
+        ("lektorproject", "INI"),
+        ("less", "Less"),
+        ("lex", "Lex"),
+        ("lfe", "LFE"),
+        ("lgt", "Logtalk"),
+        ("lhs", "Literate Haskell"),
+        ("libsonnet", "Jsonnet"),
+        ("lid", "Dylan"),
+        ("lidr", "Idris"),
+        ("ligo", "LigoLANG"),
+        ("linq", "C#"),
+        ("liquid", "Liquid"),
+        ("lisp", "Common Lisp, NewLisp"),
+        ("litcoffee", "Literate CoffeeScript"),
+        ("livecodescript", "LiveCode Script"),
+        ("livemd", "Markdown"),
+        ("lkml", "LookML"),
+        ("ll", "LLVM"),
+        ("lmi", "Python"),
+        ("logtalk", "Logtalk"),
+        ("lol", "LOLCODE"),
+        ("lookml", "LookML"),
+        ("lpr", "Pascal"),
+        ("ls", "LiveScript, LoomScript"),
+        ("lsl", "LSL"),
+        ("lslp", "LSL"),
+        ("lsp", "Common Lisp, NewLisp"),
+        ("ltx", "TeX"),
+        ("lua", "Lua"),
+        ("luau", "Luau"),
+        ("lvclass", "LabVIEW"),
+        ("lvlib", "LabVIEW"),
+        ("lvproj", "LabVIEW"),
+        ("ly", "LilyPond"),
+        ("m", "Limbo, M, MATLAB, ..."),
+        ("m2", "Macaulay2"),
+        ("m3", "Modula-3"),
+        ("m4", "M4, M4Sugar"),
+        ("ma", "Mathematica"),
+        ("mak", "Makefile"),
+        ("make", "Makefile"),
+        ("makefile", "Makefile"),
+        ("mako", "Mako"),
+        ("man", "Roff, Roff Manpage"),
+        ("mao", "Mako"),
+        ("markdown", "Markdown"),
+        ("marko", "Marko"),
+        ("mask", "Mask, Unity3D Asset"),
+        ("mat", "Unity3D Asset"),
+        ("mata", "Stata"),
+        ("matah", "Stata"),
+        ("mathematica", "Mathematica"),
+        ("matlab", "MATLAB"),
+        ("mawk", "Awk"),
+        ("maxhelp", "Max"),
+        ("maxpat", "Max"),
+        ("maxproj", "Max"),
+        ("mbox", "E-mail"),
+        ("mbt", "MoonBit"),
+        ("mc", "M4, Monkey C, Win32 Message File"),
+        ("mcfunction", "mcfunction"),
+        ("mcmeta", "JSON"),
+        ("mcr", "MAXScript"),
+        ("md", "GCC Machine Description, Markdown"),
+        ("md2", "Checksums"),
+        ("md4", "Checksums"),
+        ("md5", "Checksums"),
+        ("mdoc", "Roff, Roff Manpage"),
+        ("mdown", "Markdown"),
+        ("mdpolicy", "XML"),
+        ("mdwn", "Markdown"),
+        ("mdx", "MDX"),
+        ("me", "Roff"),
+        ("mediawiki", "Wikitext"),
+        ("mermaid", "Mermaid"),
+        ("meta", "Unity3D Asset"),
+        ("metal", "Metal"),
+        ("mg", "Modula-3"),
+        ("minid", "MiniD"),
+        ("mint", "Mint"),
+        ("mir", "YAML"),
+        ("mirah", "Mirah"),
+        ("mjml", "XML"),
+        ("mjs", "JavaScript"),
+        ("mk", "Makefile"),
+        ("mkd", "Markdown"),
+        ("mkdn", "Markdown"),
+        ("mkdown", "Markdown"),
+        ("mkfile", "Makefile"),
+        ("mkii", "TeX"),
+        ("mkiv", "TeX"),
+        ("mkvi", "TeX"),
+        ("ml", "OCaml, Standard ML"),
+        ("ml4", "OCaml"),
+        ("mli", "OCaml"),
+        ("mligo", "CameLIGO"),
+        ("mlir", "MLIR"),
+        ("mll", "OCaml"),
+        ("mly", "OCaml"),
+        ("mm", "Objective-C++, XML"),
+        ("mmd", "Mermaid"),
+        ("mmk", "Module Management System"),
+        ("mms", "Module Management System"),
+        ("mo", "Modelica, Motoko"),
+        ("mod", "AMPL, Linux Kernel Module, Modula-2, ..."),
+        ("mojo", "Mojo, XML"),
+        ("monkey", "Monkey"),
+        ("monkey2", "Monkey"),
+        ("moo", "Mercury, Moocode"),
+        ("moon", "MoonScript"),
+        ("move", "Move"),
+        ("mpl", "JetBrains MPS"),
+        ("mps", "JetBrains MPS"),
+        ("mq4", "MQL4"),
+        ("mq5", "MQL5"),
+        ("mqh", "MQL4, MQL5"),
+        ("mrc", "mIRC Script"),
+        ("ms", "MAXScript, Roff, Unix Assembly"),
+        ("msd", "JetBrains MPS"),
+        ("msg", "omnetpp-msg"),
+        ("mspec", "Ruby"),
+        ("mss", "CartoCSS"),
+        ("mt", "Mathematica"),
+        ("mtl", "Wavefront Material"),
+        ("mtml", "MTML"),
+        ("mts", "TypeScript"),
+        ("mu", "mupad"),
+        ("mud", "ZIL"),
+        ("muf", "MUF"),
+        ("mumps", "M"),
+        ("muse", "Muse"),
+        ("mustache", "Mustache"),
+        ("mxml", "XML"),
+        ("mxt", "Max"),
+        ("mysql", "SQL"),
+        ("myt", "Myghty"),
+        ("n", "Nemerle, Roff"),
+        ("nanorc", "nanorc"),
+        ("nas", "Assembly, Nasal"),
+        ("nasl", "NASL"),
+        ("nasm", "Assembly"),
+        ("natvis", "XML"),
+        ("nawk", "Awk"),
+        ("nb", "Mathematica, Text"),
+        ("nbp", "Mathematica"),
+        ("nc", "nesC"),
+        ("ncl", "Gerber Image, NCL, Text, ..."),
+        ("ndproj", "XML"),
+        ("ne", "Nearley"),
+        ("nearley", "Nearley"),
+        ("ned", "omnetpp-ned"),
+        ("neon", "NEON"),
+        ("nf", "Nextflow"),
+        ("nginx", "Nginx"),
+        ("nginxconf", "Nginx"),
+        ("ni", "Inform 7"),
+        ("nim", "Nim"),
+        ("nim.cfg", "Nim"),
+        ("nimble", "Nim"),
+        ("nimrod", "Nim"),
+        ("nims", "Nim"),
+        ("ninja", "Ninja"),
+        ("nit", "Nit"),
+        ("nix", "Nix"),
+        ("njk", "Nunjucks"),
+        ("njs", "JavaScript"),
+        ("nl", "NL, NewLisp"),
+        ("nlogo", "NetLogo"),
+        ("no", "Text"),
+        ("nomad", "HCL"),
+        ("nproj", "XML"),
+        ("nqp", "Raku"),
+        ("nr", "Noir, Roff"),
+        ("nse", "Lua"),
+        ("nsh", "NSIS"),
+        ("nsi", "NSIS"),
+        ("nss", "NWScript"),
+        ("nu", "Nu, Nushell"),
+        ("numpy", "NumPy"),
+        ("numpyw", "NumPy"),
+        ("numsc", "NumPy"),
+        ("nuspec", "XML"),
+        ("nut", "Squirrel"),
+        ("ny", "Common Lisp"),
+        ("ob2", "Oberon"),
+        ("obj", "Wavefront Object"),
+        ("objdump", "ObjDump"),
+        ("odd", "XML"),
+        ("odin", "Object Data Instance Notation, Odin"),
+        ("ol", "Jolie"),
+        ("omgrofl", "Omgrofl"),
+        ("ooc", "ooc"),
+        ("opa", "Opa"),
+        ("opal", "Opal"),
+        ("opencl", "OpenCL"),
+        ("orc", "Csound"),
+        ("org", "Org"),
+        ("os", "1C Enterprise"),
+        ("osm", "XML"),
+        ("owl", "Web Ontology Language"),
+        ("ox", "Ox"),
+        ("oxh", "Ox"),
+        ("oxo", "Ox"),
+        ("oxygene", "Oxygene"),
+        ("oz", "Oz"),
+        ("p", "Gnuplot, OpenEdge ABL"),
+        ("p4", "P4"),
+        ("p6", "Raku"),
+        ("p6l", "Raku"),
+        ("p6m", "Raku"),
+        ("p8", "Lua"),
+        ("pac", "JavaScript"),
+        ("pact", "Pact"),
+        ("pan", "Pan"),
+        ("parrot", "Parrot"),
+        ("pas", "Pascal"),
+        ("pascal", "Pascal"),
+        ("pasm", "Parrot Assembly"),
+        ("pat", "Max"),
+        ("patch", "Diff"),
+        ("pb", "PureBasic"),
+        ("pbi", "PureBasic"),
+        ("pbt", "PowerBuilder, Protocol Buffer Text Format"),
+        ("pbtxt", "Protocol Buffer Text Format"),
+        ("pck", "PLSQL"),
+        ("pcss", "PostCSS"),
+        ("pd", "Pure Data"),
+        ("pd_lua", "Lua"),
+        ("pddl", "PDDL"),
+        ("pde", "Processing"),
+        ("peggy", "PEG.js"),
+        ("pegjs", "PEG.js"),
+        ("pep", "Pep8"),
+        ("per", "Genero per"),
+        ("perl", "Perl"),
+        ("pfa", "PostScript"),
+        ("pgsql", "PLpgSQL"),
+        ("ph", "Perl"),
+        ("php", "Hack, PHP"),
+        ("php3", "PHP"),
+        ("php4", "PHP"),
+        ("php5", "PHP"),
+        ("phps", "PHP"),
+        ("phpt", "PHP"),
+        ("phtml", "HTML+PHP"),
+        ("pic", "Pic"),
+        ("pig", "PigLatin"),
+        ("pike", "Pike"),
+        ("pir", "Parrot Internal Representation"),
+        ("pkb", "PLSQL"),
+        ("pkgproj", "XML"),
+        ("pkl", "Pickle, Pkl"),
+        ("pks", "PLSQL"),
+        ("pl", "Perl, Prolog, Raku"),
+        ("pl6", "Raku"),
+        ("plantuml", "PlantUML"),
+        ("plb", "PLSQL"),
+        ("plist", "OpenStep Property List, XML Property List"),
+        ("plot", "Gnuplot"),
+        ("pls", "PLSQL"),
+        ("plsql", "PLSQL"),
+        ("plt", "Gnuplot, Prolog"),
+        ("pluginspec", "Ruby, XML"),
+        ("plx", "Perl"),
+        ("pm", "Perl, Raku, X PixMap"),
+        ("pm6", "Raku"),
+        ("pml", "Promela"),
+        ("pmod", "Pike"),
+        ("po", "Gettext Catalog"),
+        ("pod", "Pod, Pod 6"),
+        ("pod6", "Pod 6"),
+        ("podsl", "Common Lisp"),
+        ("podspec", "Ruby"),
+        ("pogo", "PogoScript"),
+        ("polar", "Polar"),
+        ("pony", "Pony"),
+        ("por", "Portugol"),
+        ("postcss", "PostCSS"),
+        ("pot", "Gettext Catalog"),
+        ("pov", "POV-Ray SDL"),
+        ("pp", "Pascal, Puppet"),
+        ("pprx", "REXX"),
+        ("praat", "Praat"),
+        ("prawn", "Ruby"),
+        ("prc", "PLSQL, SQL"),
+        ("prefab", "Unity3D Asset"),
+        ("prefs", "INI"),
+        ("prg", "xBase"),
+        ("pri", "QMake"),
+        ("prisma", "Prisma"),
+        ("pro", "IDL, INI, Proguard, ..."),
+        ("proj", "XML"),
+        ("prolog", "Prolog"),
+        ("properties", "INI, Java Properties"),
+        ("props", "XML"),
+        ("proto", "Protocol Buffer"),
+        ("prw", "xBase"),
+        ("ps", "PostScript"),
+        ("ps1", "PowerShell"),
+        ("ps1xml", "XML"),
+        ("psc", "Papyrus"),
+        ("psc1", "XML"),
+        ("psd1", "PowerShell"),
+        ("psgi", "Perl"),
+        ("psm1", "PowerShell"),
+        ("pt", "XML"),
+        ("pub", "Public Key"),
+        ("pug", "Pug"),
+        ("puml", "PlantUML"),
+        ("purs", "PureScript"),
+        ("pwn", "Pawn"),
+        ("pxd", "Cython"),
+        ("pxi", "Cython"),
+        ("py", "Python"),
+        ("py3", "Python"),
+        ("pyde", "Python"),
+        ("pyi", "Python"),
+        ("pyp", "Python"),
+        ("pyt", "Python"),
+        ("pytb", "Python traceback"),
+        ("pyw", "Python"),
+        ("pyx", "Cython"),
+        ("q", "HiveQL, q"),
+        ("qasm", "OpenQASM"),
+        ("qbs", "QML"),
+        ("qhelp", "XML"),
+        ("ql", "CodeQL"),
+        ("qll", "CodeQL"),
+        ("qmd", "RMarkdown"),
+        ("qml", "QML"),
+        ("qs", "Q#, Qt Script"),
+        ("r", "R, Rebol, Rez"),
+        ("r2", "Rebol"),
+        ("r3", "Rebol"),
+        ("rabl", "Ruby"),
+        ("rake", "Ruby"),
+        ("raku", "Raku"),
+        ("rakumod", "Raku"),
+        ("raml", "RAML"),
+        ("raw", "Raw token data"),
+        ("razor", "HTML+Razor"),
+        ("rb", "Ruby"),
+        ("rbbas", "REALbasic"),
+        ("rbfrm", "REALbasic"),
+        ("rbi", "Ruby"),
+        ("rbmnu", "REALbasic"),
+        ("rbres", "REALbasic"),
+        ("rbs", "RBS"),
+        ("rbtbar", "REALbasic"),
+        ("rbuild", "Ruby"),
+        ("rbuistate", "REALbasic"),
+        ("rbw", "Ruby"),
+        ("rbx", "Ruby"),
+        ("rbxs", "Lua"),
+        ("rchit", "GLSL"),
+        ("rd", "R"),
+        ("rdf", "XML"),
+        ("rdoc", "RDoc"),
+        ("re", "C++, Reason"),
+        ("reb", "Rebol"),
+        ("rebol", "Rebol"),
+        ("red", "Red"),
+        ("reds", "Red"),
+        ("reek", "YAML"),
+        ("reg", "Windows Registry Entries"),
+        ("regex", "Regular Expression"),
+        ("regexp", "Regular Expression"),
+        ("rego", "Open Policy Agent"),
+        ("rei", "Reason"),
+        ("religo", "ReasonLIGO"),
+        ("res", "ReScript, XML"),
+        ("resource", "RobotFramework"),
+        ("rest", "reStructuredText"),
+        ("rest.txt", "reStructuredText"),
+        ("resx", "XML"),
+        ("rex", "REXX"),
+        ("rexx", "REXX"),
+        ("rg", "Rouge"),
+        ("rhtml", "HTML+ERB"),
+        ("ring", "Ring"),
+        ("riot", "Riot"),
+        ("rkt", "Racket"),
+        ("rktd", "Racket"),
+        ("rktl", "Racket"),
+        ("rl", "Ragel"),
+        ("rmd", "RMarkdown"),
+        ("rmiss", "GLSL"),
+        ("rnh", "RUNOFF"),
+        ("rno", "RUNOFF, Roff"),
+        ("rnw", "Sweave"),
+        ("robot", "RobotFramework"),
+        ("roc", "Roc"),
+        ("rockspec", "Lua"),
+        ("roff", "Roff"),
+        ("ron", "RON"),
+        ("ronn", "Markdown"),
+        ("rpgle", "RPGLE"),
+        ("rpy", "Python, Ren'Py"),
+        ("rq", "SPARQL"),
+        ("rs", "RenderScript, Rust, XML"),
+        ("rs.in", "Rust"),
+        ("rsc", "Rascal, RouterOS Script"),
+        ("rsh", "RenderScript"),
+        ("rss", "XML"),
+        ("rst", "reStructuredText"),
+        ("rst.txt", "reStructuredText"),
+        ("rsx", "R"),
+        ("rtf", "Rich Text Format"),
+        ("ru", "Ruby"),
+        ("ruby", "Ruby"),
+        ("rviz", "YAML"),
+        ("s", "Motorola 68K Assembly, Unix Assembly"),
+        ("sage", "Sage"),
+        ("sagews", "Sage"),
+        ("sarif", "JSON"),
+        ("sas", "SAS"),
+        ("sass", "Sass"),
+        ("sats", "ATS"),
+        ("sbt", "Scala"),
+        ("sc", "Scala, SuperCollider"),
+        ("scad", "OpenSCAD"),
+        ("scala", "Scala"),
+        ("scaml", "Scaml"),
+        ("scd", "Markdown, SuperCollider"),
+        ("sce", "Scilab"),
+        ("scenic", "Scenic"),
+        ("sch", "Eagle, KiCad Schematic, Scheme, ..."),
+        ("sci", "Scilab"),
+        ("scm", "Scheme"),
+        ("sco", "Csound Score"),
+        ("scpt", "AppleScript"),
+        ("scrbl", "Racket"),
+        ("scss", "SCSS"),
+        ("scxml", "XML"),
+        ("sdc", "Tcl"),
+        ("sed", "sed"),
+        ("self", "Self"),
+        ("service", "desktop"),
+        ("sexp", "Common Lisp"),
+        ("sfd", "Spline Font Database"),
+        ("sfproj", "XML"),
+        ("sfv", "Simple File Verification"),
+        ("sh", "Shell"),
+        ("sh-session", "ShellSession"),
+        ("sh.in", "Shell"),
+        ("sha1", "Checksums"),
+        ("sha2", "Checksums"),
+        ("sha224", "Checksums"),
+        ("sha256", "Checksums"),
+        ("sha256sum", "Checksums"),
+        ("sha3", "Checksums"),
+        ("sha384", "Checksums"),
+        ("sha512", "Checksums"),
+        ("shader", "GLSL, ShaderLab"),
+        ("shen", "Shen"),
+        ("shproj", "XML"),
+        ("sieve", "Sieve"),
+        ("sig", "Standard ML"),
+        ("sj", "Objective-J"),
+        ("sjs", "JavaScript"),
+        ("sl", "Slash"),
+        ("sld", "Scheme"),
+        ("slim", "Slim"),
+        ("slint", "Slint"),
+        ("sln", "Microsoft Visual Studio Solution"),
+        ("sls", "SaltStack, Scheme"),
+        ("sma", "Pawn"),
+        ("smali", "Smali"),
+        ("smithy", "Smithy"),
+        ("smk", "Snakemake"),
+        ("sml", "Standard ML"),
+        ("smt", "SMT"),
+        ("smt2", "SMT"),
+        ("snakefile", "Snakemake"),
+        ("snap", "Jest Snapshot"),
+        ("snip", "Vim Snippet"),
+        ("snippet", "Vim Snippet"),
+        ("snippets", "Vim Snippet"),
+        ("sol", "Gerber Image, Solidity"),
+        ("soy", "Closure Templates"),
+        ("sp", "SourcePawn"),
+        ("sparql", "SPARQL"),
+        ("spc", "PLSQL"),
+        ("spec", "Python, RPM Spec, Ruby"),
+        ("spin", "Propeller Spin"),
+        ("sps", "Scheme"),
+        ("sqf", "SQF"),
+        ("sql", "PLSQL, PLpgSQL, SQL, ..."),
+        ("sqlrpgle", "RPGLE"),
+        ("sra", "PowerBuilder"),
+        ("srdf", "XML"),
+        ("srt", "SRecode Template, SubRip Text"),
+        ("sru", "PowerBuilder"),
+        ("srw", "PowerBuilder"),
+        ("ss", "Scheme"),
+        ("ssjs", "JavaScript"),
+        ("sss", "SugarSS"),
+        ("st", "Smalltalk, StringTemplate"),
+        ("stTheme", "XML Property List"),
+        ("stan", "Stan"),
+        ("star", "STAR, Starlark"),
+        ("sthlp", "Stata"),
+        ("stl", "STL"),
+        ("ston", "STON"),
+        ("story", "Gherkin"),
+        ("storyboard", "XML"),
+        ("sty", "TeX"),
+        ("styl", "Stylus"),
+        ("sublime-build", "JSON with Comments"),
+        ("sublime-color-scheme", "JSON with Comments"),
+        ("sublime-commands", "JSON with Comments"),
+        ("sublime-completions", "JSON with Comments"),
+        ("sublime-keymap", "JSON with Comments"),
+        ("sublime-macro", "JSON with Comments"),
+        ("sublime-menu", "JSON with Comments"),
+        ("sublime-mousemap", "JSON with Comments"),
+        ("sublime-project", "JSON with Comments"),
+        ("sublime-settings", "JSON with Comments"),
+        ("sublime-snippet", "XML"),
+        ("sublime-syntax", "YAML"),
+        ("sublime-theme", "JSON with Comments"),
+        ("sublime-workspace", "JSON with Comments"),
+        ("sublime_metrics", "JSON with Comments"),
+        ("sublime_session", "JSON with Comments"),
+        ("sv", "SystemVerilog"),
+        ("svelte", "Svelte"),
+        ("svg", "SVG"),
+        ("svh", "SystemVerilog"),
+        ("sw", "Sway, XML"),
+        ("swift", "Swift"),
+        ("syntax", "YAML"),
+        ("t", "Perl, Raku, Terra, ..."),
+        ("tab", "SQL"),
+        ("tac", "Python"),
+        ("tact", "JSON, Tact"),
+        ("tag", "Java Server Pages"),
+        ("talon", "Talon"),
+        ("targets", "XML"),
+        ("tcc", "C++"),
+        ("tcl", "Tcl"),
+        ("tcl.in", "Tcl"),
+        ("tcsh", "Tcsh"),
+        ("te", "SELinux Policy"),
+        ("tea", "Tea"),
+        ("templ", "templ"),
+        ("tesc", "GLSL"),
+        ("tese", "GLSL"),
+        ("tex", "TeX"),
+        ("texi", "Texinfo"),
+        ("texinfo", "Texinfo"),
+        ("textile", "Textile"),
+        ("textproto", "Protocol Buffer Text Format"),
+        ("tf", "HCL"),
+        ("tfstate", "JSON"),
+        ("tfstate.backup", "JSON"),
+        ("tftpl", "Terraform Template"),
+        ("tfvars", "HCL"),
+        ("thor", "Ruby"),
+        ("thrift", "Thrift"),
+        ("thy", "Isabelle"),
+        ("tl", "Type Language"),
+        ("tla", "TLA"),
+        ("tlv", "TL-Verilog"),
+        ("tm", "Tcl"),
+        ("tmCommand", "XML Property List"),
+        ("tmLanguage", "XML Property List"),
+        ("tmPreferences", "XML Property List"),
+        ("tmSnippet", "XML Property List"),
+        ("tmTheme", "XML Property List"),
+        ("tmac", "Roff"),
+        ("tml", "XML"),
+        ("tmux", "Shell"),
+        ("toc", "TeX, World of Warcraft Addon Data"),
+        ("toit", "Toit"),
+        ("toml", "TOML"),
+        ("tool", "Shell"),
+        ("topojson", "JSON"),
+        ("tpb", "PLSQL"),
+        ("tpl", "Smarty"),
+        ("tpp", "C++"),
+        ("tps", "PLSQL"),
+        ("tres", "Godot Resource"),
+        ("trg", "PLSQL"),
+        ("trigger", "Apex, Shell"),
+        ("ts", "TypeScript, XML"),
+        ("tscn", "Godot Resource"),
+        ("tsp", "TSPLIB data, TypeSpec"),
+        ("tst", "GAP, Scilab"),
+        ("tsv", "TSV"),
+        ("tsx", "TSX, XML"),
+        ("ttl", "Turtle"),
+        ("tu", "Turing"),
+        ("twig", "Twig"),
+        ("txi", "Texinfo"),
+        ("txl", "TXL"),
+        ("txt", "Adblock Filter List, Text, Vim Help File"),
+        ("txx", "C++"),
+        ("typ", "Typst, XML"),
+        ("uc", "UnrealScript"),
+        ("udf", "SQL"),
+        ("udo", "Csound"),
+        ("ui", "XML"),
+        ("unity", "Unity3D Asset"),
+        ("uno", "Uno"),
+        ("upc", "Unified Parallel C"),
+        ("ur", "UrWeb"),
+        ("urdf", "XML"),
+        ("url", "INI"),
+        ("urs", "UrWeb"),
+        ("ux", "XML"),
+        ("v", "Coq, V, Verilog"),
+        ("vala", "Vala"),
+        ("vapi", "Vala"),
+        ("vark", "Gosu"),
+        ("vb", "Visual Basic .NET"),
+        ("vba", "VBA, Vim Script"),
+        ("vbhtml", "Visual Basic .NET"),
+        ("vbproj", "XML"),
+        ("vbs", "VBScript"),
+        ("vcf", "TSV, vCard"),
+        ("vcl", "VCL"),
+        ("vcxproj", "XML"),
+        ("vdf", "Valve Data Format"),
+        ("veo", "Verilog"),
+        ("vert", "GLSL"),
+        ("vh", "SystemVerilog"),
+        ("vhd", "VHDL"),
+        ("vhdl", "VHDL"),
+        ("vhf", "VHDL"),
+        ("vhi", "VHDL"),
+        ("vho", "VHDL"),
+        ("vhost", "ApacheConf, Nginx"),
+        ("vhs", "VHDL"),
+        ("vht", "VHDL"),
+        ("vhw", "VHDL"),
+        ("vim", "Vim Script"),
+        ("vimrc", "Vim Script"),
+        ("viw", "SQL"),
+        ("vmb", "Vim Script"),
+        ("volt", "Volt"),
+        ("vrx", "GLSL"),
+        ("vs", "GLSL"),
+        ("vsh", "GLSL"),
+        ("vshader", "GLSL"),
+        ("vsixmanifest", "XML"),
+        ("vssettings", "XML"),
+        ("vstemplate", "XML"),
+        ("vtl", "Velocity Template Language"),
+        ("vtt", "WebVTT"),
+        ("vue", "Vue"),
+        ("vw", "PLSQL"),
+        ("vxml", "XML"),
+        ("vy", "Vyper"),
+        ("w", "CWeb, OpenEdge ABL"),
+        ("wast", "WebAssembly"),
+        ("wat", "WebAssembly"),
+        ("watchr", "Ruby"),
+        ("wdl", "WDL"),
+        ("webapp", "JSON"),
+        ("webidl", "WebIDL"),
+        ("webmanifest", "JSON"),
+        ("weechatlog", "IRC log"),
+        ("wgsl", "WGSL"),
+        ("whiley", "Whiley"),
+        ("wiki", "Wikitext"),
+        ("wikitext", "Wikitext"),
+        ("wisp", "wisp"),
+        ("wit", "WebAssembly Interface Type"),
+        ("wixproj", "XML"),
+        ("wl", "Mathematica"),
+        ("wlk", "Wollok"),
+        ("wlt", "Mathematica"),
+        ("wlua", "Lua"),
+        ("workbook", "Markdown"),
+        ("workflow", "HCL, XML"),
+        ("wren", "Wren"),
+        ("ws", "Witcher Script"),
+        ("wsdl", "XML"),
+        ("wsf", "XML"),
+        ("wsgi", "Python"),
+        ("wxi", "XML"),
+        ("wxl", "XML"),
+        ("wxs", "XML"),
+        ("x", "DirectX 3D File, Linker Script, Logos, ..."),
+        ("x10", "X10"),
+        ("x3d", "XML"),
+        ("x68", "Motorola 68K Assembly"),
+        ("xacro", "XML"),
+        ("xaml", "XML"),
+        ("xbm", "X BitMap"),
+        ("xc", "XC"),
+        ("xdc", "Tcl"),
+        ("xht", "HTML"),
+        ("xhtml", "HTML"),
+        ("xi", "Logos"),
+        ("xib", "XML"),
+        ("xlf", "XML"),
+        ("xliff", "XML"),
+        ("xm", "Logos"),
+        ("xmi", "XML"),
+        ("xml", "XML"),
+        ("xml.dist", "XML"),
+        ("xmp", "XML"),
+        ("xojo_code", "Xojo"),
+        ("xojo_menu", "Xojo"),
+        ("xojo_report", "Xojo"),
+        ("xojo_script", "Xojo"),
+        ("xojo_toolbar", "Xojo"),
+        ("xojo_window", "Xojo"),
+        ("xpl", "XProc"),
+        ("xpm", "X PixMap"),
+        ("xproc", "XProc"),
+        ("xproj", "XML"),
+        ("xpy", "Python"),
+        ("xq", "XQuery"),
+        ("xql", "XQuery"),
+        ("xqm", "XQuery"),
+        ("xquery", "XQuery"),
+        ("xqy", "XQuery"),
+        ("xrl", "Erlang"),
+        ("xs", "XS"),
+        ("xsd", "XML"),
+        ("xsh", "Xonsh"),
+        ("xsjs", "JavaScript"),
+        ("xsjslib", "JavaScript"),
+        ("xsl", "XSLT"),
+        ("xslt", "XSLT"),
+        ("xsp-config", "XPages"),
+        ("xsp.metadata", "XPages"),
+        ("xspec", "XML"),
+        ("xtend", "Xtend"),
+        ("xul", "XML"),
+        ("xzap", "ZAP"),
+        ("y", "Yacc"),
+        ("yacc", "Yacc"),
+        ("yaml", "MiniYAML, OASv2-yaml, OASv3-yaml, ..."),
+        ("yaml-tmlanguage", "YAML"),
+        ("yaml.sed", "YAML"),
+        ("yang", "YANG"),
+        ("yap", "Prolog"),
+        ("yar", "YARA"),
+        ("yara", "YARA"),
+        ("yasnippet", "YASnippet"),
+        ("yml", "MiniYAML, OASv2-yaml, OASv3-yaml, ..."),
+        ("yml.mysql", "YAML"),
+        ("yrl", "Erlang"),
+        ("yul", "Yul"),
+        ("yy", "JSON, Yacc"),
+        ("yyp", "JSON"),
+        ("zap", "ZAP"),
+        ("zcml", "XML"),
+        ("zeek", "Zeek"),
+        ("zep", "Zephir"),
+        ("zig", "Zig"),
+        ("zig.zon", "Zig"),
+        ("zil", "ZIL"),
+        ("zimpl", "Zimpl"),
+        ("zmpl", "Zimpl"),
+        ("zone", "DNS Zone"),
+        ("zpl", "Zimpl"),
+        ("zs", "ZenScript"),
+        ("zsh", "Shell"),
+        ("zsh-theme", "Shell"),
+    ]);
+
+    map
+});
+
+// Build files and dotfiles that are identified by their full name
+static SOURCE_FILENAMES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    let mut map = HashMap::new();
+
+    map.extend([
+        (".bash_aliases", "Shell"),
+        (".bash_logout", "Shell"),
+        (".bash_profile", "Shell"),
+        (".bashrc", "Shell"),
+        (".editorconfig", "EditorConfig"),
+        (".env", "Dotenv"),
+        (".env.development", "Dotenv"),
+        (".env.example", "Dotenv"),
+        (".env.local", "Dotenv"),
+        (".env.production", "Dotenv"),
+        (".env.test", "Dotenv"),
+        (".gitattributes", "Git Attributes"),
+        (".gitignore", "Ignore List"),
+        (".dockerignore", "Ignore List"),
+        (".justfile", "Just"),
+        (".profile", "Shell"),
+        (".vimrc", "Vim Script"),
+        (".zlogin", "Shell"),
+        (".zprofile", "Shell"),
+        (".zshenv", "Shell"),
+        (".zshrc", "Shell"),
+        ("BUILD", "Starlark"),
+        ("BUILD.bazel", "Starlark"),
+        ("Berksfile", "Ruby"),
+        ("Brewfile", "Ruby"),
+        ("CMakeLists.txt", "CMake"),
+        ("Caddyfile", "Caddyfile"),
+        ("Capfile", "Ruby"),
+        ("Cargo.lock", "TOML"),
+        ("Containerfile", "Dockerfile"),
+        ("Dockerfile", "Dockerfile"),
+        ("GNUmakefile", "Makefile"),
+        ("Gemfile", "Ruby"),
+        ("Guardfile", "Ruby"),
+        ("Jenkinsfile", "Groovy"),
+        ("Justfile", "Just"),
+        ("Kconfig", "Kconfig"),
+        ("Makefile", "Makefile"),
+        ("Pipfile", "TOML"),
+        ("Podfile", "Ruby"),
+        ("Procfile", "Procfile"),
+        ("Rakefile", "Ruby"),
+        ("SConscript", "Python"),
+        ("SConstruct", "Python"),
+        ("Snakefile", "Snakemake"),
+        ("Tiltfile", "Starlark"),
+        ("Vagrantfile", "Ruby"),
+        ("WORKSPACE", "Starlark"),
+        ("WORKSPACE.bazel", "Starlark"),
+        ("justfile", "Just"),
+        ("makefile", "Makefile"),
+        ("go.sum", "Go Checksums"),
+        ("meson.build", "Meson"),
+        ("poetry.lock", "TOML"),
+        ("yarn.lock", "YAML"),
+    ]);
+
+    map
+});
+
+// Interpreters named in shebang lines, with version suffixes stripped
+static SHEBANG_INTERPRETERS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    let mut map = HashMap::new();
+
+    map.extend([
+        ("ash", "Shell"),
+        ("awk", "Awk"),
+        ("bash", "Shell"),
+        ("bun", "JavaScript"),
+        ("dash", "Shell"),
+        ("deno", "TypeScript"),
+        ("elixir", "Elixir"),
+        ("escript", "Erlang"),
+        ("fish", "fish"),
+        ("gawk", "Awk"),
+        ("groovy", "Groovy"),
+        ("julia", "Julia"),
+        ("ksh", "Shell"),
+        ("lua", "Lua"),
+        ("luajit", "Lua"),
+        ("make", "Makefile"),
+        ("node", "JavaScript"),
+        ("nodejs", "JavaScript"),
+        ("osascript", "AppleScript"),
+        ("perl", "Perl"),
+        ("php", "PHP"),
+        ("pwsh", "PowerShell"),
+        ("python", "Python"),
+        ("Rscript", "R"),
+        ("ruby", "Ruby"),
+        ("runhaskell", "Haskell"),
+        ("sh", "Shell"),
+        ("tclsh", "Tcl"),
+        ("ts-node", "TypeScript"),
+        ("wish", "Tcl"),
+        ("zsh", "Shell"),
     ]);
 
-    set
+    map
 });
 
-/// Checks if a given path is a source code file based on its extension
-pub fn is_source_file(path: &Path) -> bool {
-    path.extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| SOURCE_EXTENSIONS.contains(ext.to_lowercase().as_str()))
-        .unwrap_or(false)
+/// Checks if a given path is a source code file based on its extension,
+/// its exact filename, or the shebang line of an extensionless script
+pub fn is_source_file(path: &Path, custom: &CustomTypes) -> bool {
+    detect_language(path, custom).is_some()
+}
+
+/// Finds the table entry for a path's extension, trying compound suffixes
+/// longest first so `build.zig.zon` matches `zig.zon` before `zon`.
+/// Lowercase entries match case-insensitively, mixed-case entries such as
+/// `PcbDoc` only match exactly. A disabled extension anywhere in the
+/// chain disables the match, so disabling `ts` also covers `.d.ts`.
+fn extension_match(
+    path: &Path,
+    disabled: &HashSet<String>,
+) -> Option<(&'static str, &'static str)> {
+    let name = path.file_name()?.to_str()?;
+    // Leading dots belong to the stem, `.eslintrc.json` has just `json`
+    let (_, extensions) = name.trim_start_matches('.').split_once('.')?;
+
+    let lowercase = extensions.to_lowercase();
+    let mut suffix = lowercase.as_str();
+    loop {
+        if disabled.contains(suffix) {
+            return None;
+        }
+        match suffix.split_once('.') {
+            Some((_, rest)) => suffix = rest,
+            None => break,
+        }
+    }
+
+    let mut suffix = extensions;
+    loop {
+        let found = SOURCE_EXTENSIONS
+            .get_key_value(suffix)
+            .or_else(|| SOURCE_EXTENSIONS.get_key_value(suffix.to_lowercase().as_str()));
+        if let Some((ext, languages)) = found {
+            return Some((ext, languages));
+        }
+        suffix = suffix.split_once('.')?.1;
+    }
+}
+
+/// Broad kind of a language, following linguist's grouping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LanguageCategory {
+    Programming,
+    Markup,
+    Data,
+    Prose,
+}
+
+/// A language identified for a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Language {
+    pub name: &'static str,
+    pub category: LanguageCategory,
+}
+
+impl Language {
+    fn named(name: &'static str) -> Self {
+        Self {
+            name,
+            category: language_category(name),
+        }
+    }
+
+    /// Info string for a Markdown code fence, as understood by common highlighters
+    pub fn fence_tag(&self) -> String {
+        match self.name {
+            "Batchfile" => "bat".to_string(),
+            "C#" => "csharp".to_string(),
+            "C++" => "cpp".to_string(),
+            "Emacs Lisp" => "elisp".to_string(),
+            "F#" => "fsharp".to_string(),
+            "Graphviz (DOT)" => "dot".to_string(),
+            "JSON with Comments" => "jsonc".to_string(),
+            "Protocol Buffer" => "protobuf".to_string(),
+            "Q#" => "qsharp".to_string(),
+            "Vim Script" => "vim".to_string(),
+            name => name.to_lowercase().replace(' ', "-"),
+        }
+    }
+
+    /// Name used to select the language with `--type`
+    pub fn type_name(&self) -> String {
+        match self.name {
+            // TSX is its own language to linguist, but nobody filters for it separately
+            "TSX" => "typescript".to_string(),
+            _ => self.fence_tag(),
+        }
+    }
+}
+
+fn language_category(name: &str) -> LanguageCategory {
+    match name {
+        "CSV"
+        | "Checksums"
+        | "Diff"
+        | "Dotenv"
+        | "EditorConfig"
+        | "Git Attributes"
+        | "Go Checksums"
+        | "Graphviz (DOT)"
+        | "HOCON"
+        | "INI"
+        | "Ignore List"
+        | "JSON"
+        | "JSON with Comments"
+        | "JSON5"
+        | "JSONLD"
+        | "Java Properties"
+        | "Jest Snapshot"
+        | "MiniYAML"
+        | "NEON"
+        | "OpenStep Property List"
+        | "Protocol Buffer Text Format"
+        | "RON"
+        | "SVG"
+        | "TOML"
+        | "TSV"
+        | "XML"
+        | "XML Property List"
+        | "YAML"
+        | "edn" => LanguageCategory::Data,
+        "Astro" | "BibTeX" | "CSS" | "Handlebars" | "Haml" | "HTML" | "HTML+ERB" | "HTML+PHP"
+        | "HTML+Razor" | "Jinja" | "Jupyter Notebook" | "Less" | "Liquid" | "MDX" | "Mustache"
+        | "Nunjucks" | "PostCSS" | "Pug" | "Roff" | "Roff Manpage" | "SCSS" | "Sass" | "Slim"
+        | "Stylus" | "Svelte" | "TeX" | "Texinfo" | "Twig" | "Typst" | "Vue" => {
+            LanguageCategory::Markup
+        }
+        "AsciiDoc" | "Creole" | "Gemini" | "Markdown" | "Org" | "Pod" | "Pod 6" | "RDoc"
+        | "RMarkdown" | "Text" | "Textile" | "Wikitext" | "reStructuredText" => {
+            LanguageCategory::Prose
+        }
+        _ => LanguageCategory::Programming,
+    }
+}
+
+/// Identifies a file's language from its filename, extension, or shebang line,
+/// in that order, reading the file only when the name alone is ambiguous
+pub fn detect_language(path: &Path, custom: &CustomTypes) -> Option<Language> {
+    identify_language(custom, path, || read_head(path).map(Cow::Owned))
+}
+
+/// Like [`detect_language`], for content that has already been read
+pub fn detect_language_in(path: &Path, content: &str, custom: &CustomTypes) -> Option<Language> {
+    identify_language(custom, path, || Some(Cow::Borrowed(content)))
+}
+
+fn identify_language<'a>(
+    custom: &CustomTypes,
+    path: &Path,
+    head: impl FnOnce() -> Option<Cow<'a, str>>,
+) -> Option<Language> {
+    if let Some(name) = custom.language(path).or_else(|| filename_language(path)) {
+        return Some(Language::named(name));
+    }
+
+    let name = match path.extension() {
+        Some(_) => {
+            let (ext, languages) = extension_match(path, &custom.disabled_extensions)?;
+            let guessed = match ext {
+                "al" | "h" | "pl" => head().and_then(|text| disambiguate(ext, &text)),
+                _ => None,
+            };
+            guessed.unwrap_or_else(|| extension_language(ext, languages))
+        }
+        None => parse_shebang(head()?.lines().next()?)?,
+    };
+    Some(Language::named(name))
+}
+
+/// File types added, remapped or disabled in the config, layered over the
+/// built-in tables
+#[derive(Debug, Clone, Default)]
+pub struct CustomTypes {
+    extensions: HashMap<String, &'static str>,
+    filenames: HashMap<String, &'static str>,
+    disabled_extensions: HashSet<String>,
+}
+
+/// Configured language names, leaked once each so a detected `Language`
+/// stays `Copy` like the built-in ones, however often the config is loaded
+static CUSTOM_NAMES: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+fn intern(name: &str) -> &'static str {
+    let mut names = CUSTOM_NAMES.lock().unwrap_or_else(|e| e.into_inner());
+    match names.get(name) {
+        Some(&interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(name.into());
+            names.insert(interned);
+            interned
+        }
+    }
+}
+
+impl CustomTypes {
+    /// Extra extensions and filenames are their own language unless a
+    /// mapping names one. Mapping keys starting with a dot are extensions.
+    pub fn from_config(config: &Config) -> Self {
+        let normalize = |ext: &str| ext.trim_start_matches('.').to_lowercase();
+        let mut custom = Self::default();
+
+        for ext in &config.extra_extensions {
+            let ext = normalize(ext);
+            custom.extensions.insert(ext.clone(), intern(&ext));
+        }
+        for name in &config.extra_filenames {
+            custom.filenames.insert(name.clone(), intern(name));
+        }
+        for (key, language) in &config.language_mappings {
+            if key.starts_with('.') {
+                custom.extensions.insert(normalize(key), intern(language));
+            } else {
+                custom.filenames.insert(key.clone(), intern(language));
+            }
+        }
+        custom.disabled_extensions = config
+            .disabled_extensions
+            .iter()
+            .map(|ext| normalize(ext))
+            .collect();
+
+        custom
+    }
+
+    /// Language for a configured filename or extension, longest suffix first
+    fn language(&self, path: &Path) -> Option<&'static str> {
+        let name = path.file_name()?.to_str()?;
+        if let Some(&language) = self.filenames.get(name) {
+            return Some(language);
+        }
+
+        let (_, extensions) = name.trim_start_matches('.').split_once('.')?;
+        let mut suffix = extensions;
+        loop {
+            if let Some(&language) = self.extensions.get(&suffix.to_lowercase()) {
+                return Some(language);
+            }
+            suffix = suffix.split_once('.')?.1;
+        }
+    }
+}
+
+/// The language an extension means when there is no content to go on
+fn extension_language(ext: &str, languages: &'static str) -> &'static str {
+    preferred_language(ext).unwrap_or_else(|| languages.split(',').next().unwrap_or("").trim())
+}
+
+/// Every `--type` name with the extensions and filenames it selects
+pub fn known_types(custom: &CustomTypes) -> BTreeMap<String, BTreeSet<String>> {
+    let mut types: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for (&ext, &languages) in SOURCE_EXTENSIONS.iter() {
+        let lowercase = ext.to_lowercase();
+        if custom.extensions.contains_key(&lowercase)
+            || custom.disabled_extensions.contains(&lowercase)
+        {
+            continue;
+        }
+        let glob = format!("*.{}", ext);
+        types
+            .entry(Language::named(extension_language(ext, languages)).type_name())
+            .or_default()
+            .insert(glob.clone());
+
+        // Extensions resolved by content can belong to any of their languages
+        if matches!(ext, "al" | "h" | "pl") {
+            for language in languages.split(',').map(str::trim) {
+                types
+                    .entry(Language::named(language).type_name())
+                    .or_default()
+                    .insert(glob.clone());
+            }
+        }
+    }
+    for (&name, &language) in SOURCE_FILENAMES.iter() {
+        if custom.filenames.contains_key(name) {
+            continue;
+        }
+        types
+            .entry(Language::named(language).type_name())
+            .or_default()
+            .insert(name.to_string());
+    }
+
+    let custom_globs = custom
+        .extensions
+        .iter()
+        .map(|(ext, &language)| (format!("*.{}", ext), language))
+        .chain(
+            custom
+                .filenames
+                .iter()
+                .map(|(name, &language)| (name.clone(), language)),
+        );
+    for (glob, language) in custom_globs {
+        types
+            .entry(Language::named(language).type_name())
+            .or_default()
+            .insert(glob);
+    }
+
+    types
+}
+
+/// Selects files by language, as given to `--type` and `--type-not`
+#[derive(Debug, Clone, Default)]
+pub struct TypeFilter {
+    selected: HashSet<String>,
+    excluded: HashSet<String>,
+    custom: CustomTypes,
+}
+
+impl TypeFilter {
+    /// Builds a filter from type names, also accepting an extension such as
+    /// `ts` or `py` in place of the type it belongs to
+    pub fn new(types: &[String], types_not: &[String], custom: &CustomTypes) -> Result<Self> {
+        let known = known_types(custom);
+        let resolve = |names: &[String]| -> Result<HashSet<String>> {
+            names
+                .iter()
+                .map(|name| {
+                    let name = name.trim().to_lowercase();
+                    if known.contains_key(&name) {
+                        return Ok(name);
+                    }
+                    if let Some(language) = custom.extensions.get(&name) {
+                        return Ok(Language::named(language).type_name());
+                    }
+                    match SOURCE_EXTENSIONS.get_key_value(name.as_str()) {
+                        Some((ext, languages)) => {
+                            Ok(Language::named(extension_language(ext, languages)).type_name())
+                        }
+                        None => bail!("Unknown type '{}' (see --type-list)", name),
+                    }
+                })
+                .collect()
+        };
+
+        Ok(Self {
+            selected: resolve(types)?,
+            excluded: resolve(types_not)?,
+            custom: custom.clone(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty() && self.excluded.is_empty()
+    }
+
+    /// Files of no known language only pass when no types were selected
+    pub fn matches(&self, path: &Path) -> bool {
+        match detect_language(path, &self.custom).map(|language| language.type_name()) {
+            Some(name) => {
+                (self.selected.is_empty() || self.selected.contains(&name))
+                    && !self.excluded.contains(&name)
+            }
+            None => self.selected.is_empty(),
+        }
+    }
+}
+
+fn read_head(path: &Path) -> Option<String> {
+    let mut bytes = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Picks between the languages sharing an extension by looking for constructs
+/// only one of them uses. `None` leaves the choice to [`preferred_language`].
+fn disambiguate(ext: &str, text: &str) -> Option<&'static str> {
+    let starts_any = |prefixes: &[&str]| {
+        text.lines()
+            .map(str::trim_start)
+            .any(|line| prefixes.iter().any(|prefix| line.starts_with(prefix)))
+    };
+
+    match ext {
+        "h" if starts_any(&["@interface", "@protocol", "@end", "#import"]) => Some("Objective-C"),
+        "h" if starts_any(&["class ", "namespace ", "template", "public:", "private:"])
+            || text.contains("std::") =>
+        {
+            Some("C++")
+        }
+        "pl" if starts_any(&["use v6", "unit ", "my class ", "sub MAIN"]) => Some("Raku"),
+        // Prolog directives start with `:-` and rule heads end with it
+        "pl" if text
+            .lines()
+            .any(|line| line.starts_with(":-") || line.trim_end().ends_with(":-")) =>
+        {
+            Some("Prolog")
+        }
+        // AL objects are declared as `codeunit 50100 "Name"`
+        "al" if text.lines().any(|line| {
+            let mut words = line.split_whitespace();
+            matches!(
+                words.next(),
+                Some(
+                    "codeunit"
+                        | "enum"
+                        | "enumextension"
+                        | "page"
+                        | "pageextension"
+                        | "query"
+                        | "report"
+                        | "table"
+                        | "tableextension"
+                        | "xmlport"
+                )
+            ) && words
+                .next()
+                .is_some_and(|id| id.bytes().all(|b| b.is_ascii_digit()))
+        }) =>
+        {
+            Some("AL")
+        }
+        _ => None,
+    }
+}
+
+fn filename_language(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    SOURCE_FILENAMES.get(name).copied()
+}
+
+fn parse_shebang(line: &str) -> Option<&'static str> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+
+    // `#!/usr/bin/env -S python3 -u` names the interpreter after env's own flags
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+
+    let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    SHEBANG_INTERPRETERS.get(name).copied()
+}
+
+// The table lists languages alphabetically, so ambiguous extensions need
+// an explicit pick for the language people almost always mean
+fn preferred_language(ext: &str) -> Option<&'static str> {
+    match ext {
+        "al" | "pl" => Some("Perl"),
+        "cfg" => Some("INI"),
+        "cs" => Some("C#"),
+        "d" => Some("D"),
+        "h" => Some("C"),
+        "html" => Some("HTML"),
+        "inc" => Some("C++"),
+        "json" => Some("JSON"),
+        "m" => Some("Objective-C"),
+        "md" => Some("Markdown"),
+        "php" => Some("PHP"),
+        "r" => Some("R"),
+        "rs" => Some("Rust"),
+        "sql" => Some("SQL"),
+        "ts" => Some("TypeScript"),
+        "txt" => Some("Text"),
+        "v" => Some("Verilog"),
+        "yaml" | "yml" => Some("YAML"),
+        _ => None,
+    }
+}
+
+/// How much of a file is inspected when sniffing its content
+const SNIFF_LEN: usize = 8 * 1024;
+
+/// What a file's leading bytes say about its content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Binary,
+}
+
+/// Classifies content from its first few KB. NUL bytes mean binary unless a
+/// UTF-16 byte order mark explains them, otherwise the sample must be UTF-8.
+pub fn sniff_content(bytes: &[u8]) -> ContentKind {
+    match bytes {
+        [0xff, 0xfe, ..] => return ContentKind::Utf16Le,
+        [0xfe, 0xff, ..] => return ContentKind::Utf16Be,
+        _ => {}
+    }
+
+    let sample = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if sample.contains(&0) {
+        return ContentKind::Binary;
+    }
+
+    match std::str::from_utf8(sample) {
+        Ok(_) => ContentKind::Utf8,
+        // A multi-byte character cut off by the end of the sample is fine
+        Err(e) if e.error_len().is_none() && sample.len() < bytes.len() => ContentKind::Utf8,
+        Err(_) => ContentKind::Binary,
+    }
+}
+
+/// Decodes file content as text, or returns `None` when it sniffs as binary
+pub fn decode_text(bytes: Vec<u8>) -> Option<String> {
+    let utf16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units).ok()
+    };
+
+    match sniff_content(&bytes) {
+        ContentKind::Utf8 => {
+            let text = String::from_utf8(bytes).ok()?;
+            Some(
+                text.strip_prefix('\u{feff}')
+                    .map(str::to_string)
+                    .unwrap_or(text),
+            )
+        }
+        ContentKind::Utf16Le => utf16(&bytes, u16::from_le_bytes),
+        ContentKind::Utf16Be => utf16(&bytes, u16::from_be_bytes),
+        ContentKind::Binary => None,
+    }
+}
+
+/// Ranks how useful a file is for orienting in a codebase, lower is more important.
+/// READMEs come first, then project manifests, then entry points.
+pub fn importance_rank(path: &Path) -> usize {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return 3;
+    };
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(name)
+        .to_lowercase();
+
+    if stem == "readme" {
+        0
+    } else if MANIFEST_FILENAMES.contains(&name) {
+        1
+    } else if ENTRY_POINT_STEMS.contains(&stem.as_str()) {
+        2
+    } else {
+        3
+    }
+}
+
+const MANIFEST_FILENAMES: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "requirements.txt",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "Gemfile",
+    "composer.json",
+    "mix.exs",
+    "deno.json",
+    "flake.nix",
+    "CMakeLists.txt",
+    "Makefile",
+    "Dockerfile",
+];
+
+const ENTRY_POINT_STEMS: &[&str] = &[
+    "main", "lib", "index", "app", "__init__", "__main__", "server", "mod",
+];
+
+/// Why a file is machine-made rather than written for this project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provenance {
+    Generated,
+    Vendored,
+    Minified,
 }
 
+/// Flags generated, vendored and minified files, which cost a lot of tokens
+/// and tell the reader little. `path` is relative to the analyzed directory.
+pub fn classify_provenance(path: &Path, content: &str) -> Option<Provenance> {
+    let is_vendored = path.parent().is_some_and(|parent| {
+        parent
+            .components()
+            .any(|c| VENDOR_DIRS.contains(&c.as_os_str().to_str().unwrap_or("")))
+    });
+    if is_vendored {
+        return Some(Provenance::Vendored);
+    }
+
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    if GENERATED_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    {
+        return Some(Provenance::Generated);
+    }
+    // Generators announce themselves in the first few lines
+    if content
+        .lines()
+        .take(GENERATED_HEADER_LINES)
+        .any(|line| GENERATED_MARKERS.is_match(line.trim()))
+    {
+        return Some(Provenance::Generated);
+    }
+
+    let lines = content.lines().count().max(1);
+    if name.contains(".min.") || (content.len() >= 1024 && content.len() / lines > 300) {
+        return Some(Provenance::Minified);
+    }
+
+    None
+}
+
+const VENDOR_DIRS: &[&str] = &[
+    "vendor",
+    "vendors",
+    "third_party",
+    "third-party",
+    "thirdparty",
+    "bower_components",
+    "Pods",
+];
+
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    ".pb.swift",
+    "_pb2.py",
+    "_pb2.pyi",
+    "_pb2_grpc.py",
+    "_pb.js",
+    "_pb.d.ts",
+    "_grpc_pb.js",
+    ".g.dart",
+    ".freezed.dart",
+    ".designer.cs",
+    ".generated.ts",
+];
+
+/// Headers written by specific tools, matched against whole trimmed lines so
+/// prose that merely mentions them doesn't count
+static GENERATED_MARKERS: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
+        // https://go.dev/s/generatedcode
+        r"^// Code generated .* DO NOT EDIT\.$",
+        // Facebook tooling, Diesel, Cargo
+        r"^(?://|#|--|/?\*+)\s*@generated\b",
+        // .NET tools
+        r"^//\s*<auto-generated\b",
+        r"^(?://|#)\s*Generated by the (?:protocol buffer compiler|gRPC\b.*)\.\s*DO NOT EDIT!$",
+    ])
+    .expect("generated file markers are valid")
+});
+
+const GENERATED_HEADER_LINES: usize = 10;
+
 /// Get the total number of supported extensions
 pub fn _supported_extension_count() -> usize {
     SOURCE_EXTENSIONS.len()
@@ -1425,7 +2199,408 @@ mod tests {
 
         for (file, expected) in test_cases {
             let path = PathBuf::from(file);
-            assert_eq!(is_source_file(&path), expected, "Failed for {}", file);
+            assert_eq!(
+                is_source_file(&path, &CustomTypes::default()),
+                expected,
+                "Failed for {}",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_compound_extensions() {
+        let test_cases = vec![
+            ("build.zig.zon", Some("Zig")),
+            ("views/welcome.blade.php", Some("Blade")),
+            ("page.antlers.php", Some("Antlers")),
+            ("prog.8xp.txt", Some("TI Program")),
+            ("build.gradle.kts", Some("Gradle Kotlin DSL")),
+            ("notes.txt", Some("Text")),
+            ("index.php", Some("PHP")),
+            (".eslintrc.json", Some("JSON")),
+            ("archive.tar.gz", None),
+        ];
+
+        for (file, expected) in test_cases {
+            let path = PathBuf::from(file);
+            assert_eq!(
+                detect_language(&path, &CustomTypes::default()).map(|l| l.name),
+                expected,
+                "Failed for {}",
+                file
+            );
+            assert_eq!(
+                is_source_file(&path, &CustomTypes::default()),
+                expected.is_some(),
+                "Failed for {}",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_case_sensitive_extensions() {
+        let test_cases = vec![
+            ("board.PcbDoc", true),
+            ("board.pcbdoc", false),
+            ("release.OutJob", true),
+            ("syntax.JSON-tmLanguage", true),
+            ("syntax.json-tmlanguage", false),
+            ("theme.tmTheme", true),
+            ("main.RS", true),
+            ("script.Py", true),
+        ];
+
+        for (file, expected) in test_cases {
+            let path = PathBuf::from(file);
+            assert_eq!(
+                is_source_file(&path, &CustomTypes::default()),
+                expected,
+                "Failed for {}",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_filename_detection() {
+        let test_cases = vec![
+            ("Makefile", Some("Makefile")),
+            ("docker/Dockerfile", Some("Dockerfile")),
+            ("Justfile", Some("Just")),
+            ("CMakeLists.txt", Some("CMake")),
+            ("Gemfile", Some("Ruby")),
+            (".bashrc", Some("Shell")),
+            ("LICENSE", None),
+        ];
+
+        for (file, expected) in test_cases {
+            let path = PathBuf::from(file);
+            assert_eq!(
+                detect_language(&path, &CustomTypes::default()).map(|l| l.name),
+                expected,
+                "Failed for {}",
+                file
+            );
+            assert_eq!(
+                is_source_file(&path, &CustomTypes::default()),
+                expected.is_some(),
+                "Failed for {}",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_shebang() {
+        let test_cases = vec![
+            ("#!/usr/bin/env python\n", Some("Python")),
+            ("#!/usr/bin/env python3.11\n", Some("Python")),
+            ("#!/bin/bash -e\n", Some("Shell")),
+            ("#! /usr/bin/perl -w\n", Some("Perl")),
+            ("#!/usr/bin/env -S node --no-warnings\n", Some("JavaScript")),
+            ("#!/usr/bin/env FOO=1 ruby\n", Some("Ruby")),
+            ("#!/usr/bin/unknown\n", None),
+            ("echo hello\n", None),
+        ];
+
+        for (line, expected) in test_cases {
+            assert_eq!(parse_shebang(line), expected, "Failed for {:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_shebang_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("deploy");
+        std::fs::write(&script, "#!/usr/bin/env python3\nprint('hi')\n").unwrap();
+        let binary = dir.path().join("tool");
+        std::fs::write(&binary, [0x7f, b'E', b'L', b'F']).unwrap();
+
+        assert!(is_source_file(&script, &CustomTypes::default()));
+        assert_eq!(
+            detect_language(&script, &CustomTypes::default()).map(|l| l.name),
+            Some("Python")
+        );
+        assert!(!is_source_file(&binary, &CustomTypes::default()));
+    }
+
+    #[test]
+    fn test_sniff_content() {
+        assert_eq!(sniff_content(b"fn main() {}\n"), ContentKind::Utf8);
+        assert_eq!(sniff_content("héllo wörld".as_bytes()), ContentKind::Utf8);
+        assert_eq!(sniff_content(b""), ContentKind::Utf8);
+        assert_eq!(sniff_content(b"GIF89a\x01\x00\x00"), ContentKind::Binary);
+        assert_eq!(
+            sniff_content(b"\x1f\x8b\x08\x08compressed"),
+            ContentKind::Binary
+        );
+        assert_eq!(sniff_content(b"\xff\xfeh\x00i\x00"), ContentKind::Utf16Le);
+        assert_eq!(sniff_content(b"\xfe\xff\x00h\x00i"), ContentKind::Utf16Be);
+
+        // A character split at the sample boundary doesn't make the file binary
+        let mut split = "a".repeat(SNIFF_LEN - 1).into_bytes();
+        split.extend("é and more".as_bytes());
+        assert_eq!(sniff_content(&split), ContentKind::Utf8);
+    }
+
+    #[test]
+    fn test_decode_text() {
+        assert_eq!(decode_text(b"plain\n".to_vec()).as_deref(), Some("plain\n"));
+        assert_eq!(
+            decode_text(b"\xef\xbb\xbfbom".to_vec()).as_deref(),
+            Some("bom")
+        );
+        assert_eq!(
+            decode_text(b"\xff\xfeh\x00i\x00".to_vec()).as_deref(),
+            Some("hi")
+        );
+        assert_eq!(
+            decode_text(b"\xfe\xff\x00h\x00i".to_vec()).as_deref(),
+            Some("hi")
+        );
+        assert_eq!(decode_text(b"text\x00with nul".to_vec()), None);
+    }
+
+    #[test]
+    fn test_classify_provenance() {
+        let long_line = format!("var a={};\n", "1+".repeat(800));
+        let test_cases = vec![
+            (
+                "api/user.pb.go",
+                "package api\n",
+                Some(Provenance::Generated),
+            ),
+            (
+                "gen/types.go",
+                "// Code generated by stringer. DO NOT EDIT.\n\npackage gen\n",
+                Some(Provenance::Generated),
+            ),
+            (
+                "src/schema.rs",
+                "// @generated automatically by Diesel CLI.\n",
+                Some(Provenance::Generated),
+            ),
+            (
+                "api/user_pb2_grpc.py",
+                "# Generated by the gRPC Python protocol compiler plugin. DO NOT EDIT!\n",
+                Some(Provenance::Generated),
+            ),
+            (
+                "src/render.rs",
+                "/// Writes a header, the Go tools look for DO NOT EDIT\n",
+                None,
+            ),
+            (
+                "gen/notes.go",
+                "// Code generated by hand, then edited\n",
+                None,
+            ),
+            ("src/check.rs", "let marker = \"@generated\";\n", None),
+            ("vendor/lib/x.go", "package x\n", Some(Provenance::Vendored)),
+            (
+                "web/third_party/a.js",
+                "let a;\n",
+                Some(Provenance::Vendored),
+            ),
+            ("web/app.min.js", "let a;\n", Some(Provenance::Minified)),
+            ("web/bundle.js", &long_line, Some(Provenance::Minified)),
+            ("src/vendor.rs", "fn vendor() {}\n", None),
+            ("external/api/client.go", "package api\n", None),
+            ("src/main.rs", "fn main() {}\n", None),
+        ];
+
+        for (file, content, expected) in test_cases {
+            let path = PathBuf::from(file);
+            assert_eq!(
+                classify_provenance(&path, content),
+                expected,
+                "Failed for {}",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_importance_rank() {
+        let test_cases = vec![
+            ("README.md", 0),
+            ("docs/readme.rst", 0),
+            ("Cargo.toml", 1),
+            ("web/package.json", 1),
+            ("src/main.rs", 2),
+            ("src/index.ts", 2),
+            ("src/output.rs", 3),
+        ];
+
+        for (file, expected) in test_cases {
+            let path = PathBuf::from(file);
+            assert_eq!(importance_rank(&path), expected, "Failed for {}", file);
+        }
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let test_cases = vec![
+            ("main.rs", Some("Rust")),
+            ("script.PY", Some("Python")),
+            ("glimpse.1", Some("Roff")),
+            (".env", Some("Dotenv")),
+            ("config/.env.local", Some("Dotenv")),
+            ("test.xyz", None),
+            ("LICENSE", None),
+        ];
+
+        for (file, expected) in test_cases {
+            let path = PathBuf::from(file);
+            assert_eq!(
+                detect_language(&path, &CustomTypes::default()).map(|l| l.name),
+                expected,
+                "Failed for {}",
+                file
+            );
+        }
+
+        let rust = detect_language(Path::new("main.rs"), &CustomTypes::default()).unwrap();
+        assert_eq!(rust.category, LanguageCategory::Programming);
+        assert_eq!(rust.fence_tag(), "rust");
+        let readme = detect_language(Path::new("README.md"), &CustomTypes::default()).unwrap();
+        assert_eq!(readme.category, LanguageCategory::Prose);
+        let manifest = detect_language(Path::new("Cargo.toml"), &CustomTypes::default()).unwrap();
+        assert_eq!(manifest.category, LanguageCategory::Data);
+        let page = detect_language(Path::new("index.html"), &CustomTypes::default()).unwrap();
+        assert_eq!(page.category, LanguageCategory::Markup);
+        assert_eq!(
+            detect_language(Path::new("a.cpp"), &CustomTypes::default())
+                .unwrap()
+                .fence_tag(),
+            "cpp"
+        );
+        assert_eq!(
+            detect_language(Path::new("a.cs"), &CustomTypes::default())
+                .unwrap()
+                .fence_tag(),
+            "csharp"
+        );
+    }
+
+    #[test]
+    fn test_known_types() {
+        let types = known_types(&CustomTypes::default());
+        let typescript = &types["typescript"];
+        for glob in ["*.ts", "*.tsx", "*.mts", "*.cts"] {
+            assert!(typescript.contains(glob), "typescript missing {}", glob);
         }
+        assert!(types["rust"].contains("*.rs"));
+        assert!(types["makefile"].contains("Makefile"));
+        assert!(types["cpp"].contains("*.h"));
+        assert!(types["c"].contains("*.h"));
+    }
+
+    #[test]
+    fn test_type_filter() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let filter =
+            TypeFilter::new(&names(&["rust", "ts"]), &[], &CustomTypes::default()).unwrap();
+        assert!(filter.matches(Path::new("src/main.rs")));
+        assert!(filter.matches(Path::new("web/app.tsx")));
+        assert!(filter.matches(Path::new("web/index.mts")));
+        assert!(!filter.matches(Path::new("readme.md")));
+        assert!(!filter.matches(Path::new("LICENSE")));
+
+        let filter =
+            TypeFilter::new(&[], &names(&["Markdown", "json"]), &CustomTypes::default()).unwrap();
+        assert!(filter.matches(Path::new("src/main.rs")));
+        assert!(filter.matches(Path::new("LICENSE")));
+        assert!(!filter.matches(Path::new("readme.md")));
+        assert!(!filter.matches(Path::new("package.json")));
+
+        assert!(TypeFilter::new(&[], &[], &CustomTypes::default())
+            .unwrap()
+            .is_empty());
+        assert!(TypeFilter::new(&names(&["nosuchtype"]), &[], &CustomTypes::default()).is_err());
+    }
+
+    #[test]
+    fn test_custom_types() {
+        let config: Config = toml::from_str(
+            r#"
+            extra_extensions = [".flow", "rules"]
+            disabled_extensions = ["txt", ".ts"]
+            extra_filenames = ["Taskfile"]
+
+            [language_mappings]
+            ".tpl" = "Go Template"
+            ".md" = "Text"
+            "Brewfile" = "Ruby"
+            "#,
+        )
+        .unwrap();
+        let custom = CustomTypes::from_config(&config);
+        let language = |file: &str| {
+            identify_language(&custom, Path::new(file), || None).map(|language| language.name)
+        };
+
+        assert_eq!(language("pipeline.flow"), Some("flow"));
+        assert_eq!(language("billing.RULES"), Some("rules"));
+        assert_eq!(language("site/index.tpl"), Some("Go Template"));
+        assert_eq!(language("README.md"), Some("Text"));
+        assert_eq!(language("Taskfile"), Some("Taskfile"));
+        assert_eq!(language("Brewfile"), Some("Ruby"));
+        assert_eq!(language("notes.txt"), None);
+        // Compound suffixes go with the extension they end in
+        assert_eq!(language("docs/index.rst.txt"), None);
+        assert_eq!(language("src/types.d.ts"), None);
+        assert_eq!(language("src/App.tsx"), Some("TSX"));
+        assert_eq!(language("src/main.rs"), Some("Rust"));
+    }
+
+    #[test]
+    fn test_custom_names_leak_once() {
+        let config: Config = toml::from_str(r#"extra_extensions = ["flow"]"#).unwrap();
+        let name = |custom: &CustomTypes| custom.extensions["flow"];
+
+        let first = name(&CustomTypes::from_config(&config));
+        let second = name(&CustomTypes::from_config(&config));
+        assert!(std::ptr::eq(first, second));
+    }
+
+    #[test]
+    fn test_ambiguous_extension_heuristics() {
+        let test_cases = vec![
+            ("util.h", "int add(int a, int b);\n", "C"),
+            ("util.h", "namespace util {\nclass Adder;\n}\n", "C++"),
+            (
+                "util.h",
+                "#import <Foundation/Foundation.h>\n@interface Foo\n@end\n",
+                "Objective-C",
+            ),
+            ("tool.pl", "use strict;\nmy $x = 1;\n", "Perl"),
+            (
+                "rules.pl",
+                ":- module(rules, []).\nparent(X, Y) :-\n    father(X, Y).\n",
+                "Prolog",
+            ),
+            ("tool.pl", "use v6;\nsay 'hi';\n", "Raku"),
+            ("Sales.al", "codeunit 50100 \"Sales Helper\"\n{\n}\n", "AL"),
+            ("AutoSplit.al", "sub foo {\n    return 1;\n}\n", "Perl"),
+        ];
+
+        for (file, content, expected) in test_cases {
+            let language =
+                detect_language_in(Path::new(file), content, &CustomTypes::default()).unwrap();
+            assert_eq!(language.name, expected, "Failed for {:?}", content);
+        }
+
+        // Reading from disk goes through the same heuristics
+        let dir = tempfile::tempdir().unwrap();
+        let header = dir.path().join("vec.h");
+        std::fs::write(&header, "template <typename T>\nclass Vec;\n").unwrap();
+        assert_eq!(
+            detect_language(&header, &CustomTypes::default()).map(|l| l.name),
+            Some("C++")
+        );
     }
 }
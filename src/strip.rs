@@ -0,0 +1,677 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use rayon::prelude::*;
+
+use crate::cli::StripKind;
+use crate::output::{Edit, FileEntry};
+use crate::source_detection::{Language, LanguageCategory};
+use crate::tokenizer::TokenCounter;
+
+/// Transforms run in this order, so license headers are counted as such
+/// rather than as comments
+const ORDER: [StripKind; 3] = [
+    StripKind::LicenseHeaders,
+    StripKind::Comments,
+    StripKind::BlankLines,
+];
+
+/// Strips each entry's content in place. With a counter, returns the tokens
+/// each transform saved across all entries.
+pub fn strip_entries(
+    entries: &mut [FileEntry],
+    kinds: &[StripKind],
+    counter: Option<&TokenCounter>,
+) -> Result<Vec<(StripKind, usize)>> {
+    let order: Vec<StripKind> = ORDER.into_iter().filter(|k| kinds.contains(k)).collect();
+
+    let saved_per_file = entries
+        .par_iter_mut()
+        .map(|entry| -> Result<Vec<usize>> {
+            let mut saved = Vec::new();
+            for &kind in &order {
+                let before = counter
+                    .map(|counter| counter.count_tokens(&entry.content))
+                    .transpose()?;
+                entry.splice(&strip(&entry.content, entry.language, kind));
+                if let (Some(counter), Some(before)) = (counter, before) {
+                    saved.push(before.saturating_sub(counter.count_tokens(&entry.content)?));
+                }
+            }
+            Ok(saved)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if counter.is_none() {
+        return Ok(Vec::new());
+    }
+    Ok(order
+        .iter()
+        .enumerate()
+        .map(|(i, &kind)| (kind, saved_per_file.iter().map(|saved| saved[i]).sum()))
+        .collect())
+}
+
+/// The edits that strip one kind of content from a file
+pub fn strip(content: &str, language: Option<Language>, kind: StripKind) -> Vec<Edit> {
+    let syntax = language.and_then(|language| syntax(language.name));
+    let prose = language.is_some_and(|language| language.category == LanguageCategory::Prose);
+
+    match (kind, syntax) {
+        (StripKind::Comments, Some(syntax)) => strip_comments(content, &syntax),
+        (StripKind::LicenseHeaders, Some(syntax)) => strip_license_header(content, &syntax),
+        (StripKind::BlankLines, syntax) => strip_blank_lines(content, syntax.as_ref(), prose),
+        // Without knowing the comment syntax there is nothing safe to remove
+        (_, None) => Vec::new(),
+    }
+}
+
+/// How a language writes comments and the literals that can contain them
+struct Syntax {
+    line: &'static [&'static str],
+    block: &'static [(&'static str, &'static str)],
+    /// Longest first, so `"""` wins over `"`
+    quotes: &'static [&'static str],
+    /// Quotes whose literals may span lines, others end at a newline
+    multiline: &'static [&'static str],
+    /// Raw strings and lifetimes
+    rust: bool,
+    /// Regex literals, which may hold `//`
+    regex: bool,
+}
+
+const C_LIKE: Syntax = Syntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    quotes: &["\"", "'"],
+    multiline: &[],
+    rust: false,
+    regex: false,
+};
+
+fn syntax(language: &str) -> Option<Syntax> {
+    let syntax = match language {
+        "Rust" => Syntax {
+            multiline: &["\""],
+            rust: true,
+            ..C_LIKE
+        },
+        "JavaScript" | "TypeScript" | "TSX" => Syntax {
+            quotes: &["\"", "'", "`"],
+            multiline: &["`"],
+            regex: true,
+            ..C_LIKE
+        },
+        "Go" => Syntax {
+            quotes: &["\"", "'", "`"],
+            multiline: &["`"],
+            ..C_LIKE
+        },
+        "C" | "C++" | "C#" | "Java" | "Kotlin" | "Swift" | "Scala" | "Dart" | "Objective-C"
+        | "Objective-C++" | "Groovy" | "Gradle" | "Solidity" | "Zig" | "Protocol Buffer"
+        | "GLSL" | "HLSL" | "Cuda" | "OpenCL" | "Verilog" | "SystemVerilog" | "Apex" | "Haxe"
+        | "D" | "V" | "Odin" | "Hare" | "JSON with Comments" | "JSON5" | "Jsonnet" | "Less"
+        | "SCSS" | "Processing" => C_LIKE,
+        "PHP" => Syntax {
+            line: &["//", "#"],
+            ..C_LIKE
+        },
+        "CSS" => Syntax {
+            line: &[],
+            ..C_LIKE
+        },
+        "Python" | "Starlark" | "Cython" => Syntax {
+            line: &["#"],
+            block: &[],
+            quotes: &["\"\"\"", "'''", "\"", "'"],
+            multiline: &["\"\"\"", "'''"],
+            rust: false,
+            regex: false,
+        },
+        "TOML" => Syntax {
+            line: &["#"],
+            block: &[],
+            quotes: &["\"\"\"", "'''", "\"", "'"],
+            multiline: &["\"\"\"", "'''"],
+            ..C_LIKE
+        },
+        // Strings in these run on across lines until they're closed
+        "Shell" | "Ruby" | "Perl" => Syntax {
+            line: &["#"],
+            block: &[],
+            multiline: &["\"", "'"],
+            ..C_LIKE
+        },
+        "R" | "YAML" | "Makefile" | "Dockerfile" | "CMake" | "Elixir" | "Nim" | "Julia"
+        | "PowerShell" | "Tcl" | "Awk" | "fish" | "Crystal" | "Raku" | "Nix" | "Just"
+        | "Git Attributes" | "Ignore List" => Syntax {
+            line: &["#"],
+            block: &[],
+            ..C_LIKE
+        },
+        "SQL" | "PLSQL" | "PLpgSQL" => Syntax {
+            line: &["--"],
+            quotes: &["'"],
+            ..C_LIKE
+        },
+        // Block comments first, `--[[` also starts with the line marker
+        "Lua" | "Luau" => Syntax {
+            line: &["--"],
+            block: &[("--[[", "]]")],
+            ..C_LIKE
+        },
+        "Haskell" | "Elm" | "PureScript" | "Agda" | "Idris" => Syntax {
+            line: &["--"],
+            block: &[("{-", "-}")],
+            quotes: &["\""],
+            ..C_LIKE
+        },
+        "Common Lisp" | "Emacs Lisp" | "Scheme" | "Clojure" | "Racket" | "Fennel" => Syntax {
+            line: &[";"],
+            block: &[],
+            quotes: &["\""],
+            ..C_LIKE
+        },
+        // Apostrophes in prose aren't quotes, so markup has no literals to track
+        "HTML" | "XML" | "Markdown" | "SVG" | "Vue" | "Svelte" => Syntax {
+            line: &[],
+            block: &[("<!--", "-->")],
+            quotes: &[],
+            ..C_LIKE
+        },
+        _ => return None,
+    };
+    Some(syntax)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpanKind {
+    LineComment,
+    BlockComment,
+    Literal,
+}
+
+/// Finds the comments and string literals in `content`, everything between
+/// them is code
+fn tokenize(content: &str, syntax: &Syntax) -> Vec<(Range<usize>, SpanKind)> {
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    // A shebang looks like a comment in `#` languages but has to stay
+    if content.starts_with("#!") {
+        i = content.find('\n').unwrap_or(content.len());
+    }
+
+    while i < content.len() {
+        let rest = &content[i..];
+        let previous = content[..i].chars().next_back();
+
+        if let Some((open, close)) = syntax.block.iter().find(|(open, _)| rest.starts_with(open)) {
+            let end = rest[open.len()..]
+                .find(close)
+                .map_or(content.len(), |j| i + open.len() + j + close.len());
+            spans.push((i..end, SpanKind::BlockComment));
+            i = end;
+            continue;
+        }
+
+        // `#` only starts a comment as its own word, think `$#` or `${#var}`
+        let line_marker = syntax.line.iter().find(|marker| {
+            rest.starts_with(*marker)
+                && (**marker != "#" || previous.is_none_or(char::is_whitespace))
+        });
+        if line_marker.is_some() {
+            let end = rest.find('\n').map_or(content.len(), |j| i + j);
+            spans.push((i..end, SpanKind::LineComment));
+            i = end;
+            continue;
+        }
+
+        if let Some(len) = syntax.regex.then(|| regex_len(content, i)).flatten() {
+            spans.push((i..i + len, SpanKind::Literal));
+            i += len;
+            continue;
+        }
+
+        if let Some(len) = literal_len(rest, previous, syntax) {
+            if len > 1 || !syntax.rust || !rest.starts_with('\'') {
+                spans.push((i..i + len, SpanKind::Literal));
+            }
+            i += len;
+            continue;
+        }
+
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+
+    spans
+}
+
+/// Length of the literal starting at `rest`, if one does
+fn literal_len(rest: &str, previous: Option<char>, syntax: &Syntax) -> Option<usize> {
+    let is_word_start = previous.is_none_or(|c| !c.is_alphanumeric() && c != '_');
+
+    if syntax.rust {
+        // r"..", r#".."#, br".."
+        let raw = rest.strip_prefix("br").or_else(|| rest.strip_prefix('r'));
+        if let Some(raw) = raw.filter(|_| is_word_start) {
+            let hashes = raw.len() - raw.trim_start_matches('#').len();
+            if raw[hashes..].starts_with('"') {
+                let prefix = rest.len() - raw.len() + hashes + 1;
+                let close = format!("\"{}", "#".repeat(hashes));
+                let end = rest[prefix..]
+                    .find(&close)
+                    .map_or(rest.len(), |j| prefix + j + close.len());
+                return Some(end);
+            }
+        }
+
+        // A char literal is one character or escape, anything else is a lifetime
+        if let Some(after) = rest.strip_prefix('\'') {
+            let len = match after.strip_prefix('\\') {
+                Some(escaped) => escaped.find('\'').map(|j| j + 3),
+                None => {
+                    let c = after.chars().next()?;
+                    after[c.len_utf8()..]
+                        .starts_with('\'')
+                        .then(|| c.len_utf8() + 2)
+                }
+            };
+            return Some(len.unwrap_or(1));
+        }
+    }
+
+    let quote = syntax
+        .quotes
+        .iter()
+        .find(|quote| rest.starts_with(*quote))?;
+    let multiline = syntax.multiline.contains(quote);
+    let mut j = quote.len();
+    while j < rest.len() {
+        let tail = &rest[j..];
+        if let Some(escaped) = tail.strip_prefix('\\') {
+            j += 1 + escaped.chars().next().map_or(0, char::len_utf8);
+        } else if tail.starts_with(*quote) {
+            return Some(j + quote.len());
+        } else if tail.starts_with('\n') && !multiline {
+            return Some(j);
+        } else {
+            j += tail.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    Some(rest.len())
+}
+
+/// Words a regex literal can follow, where a `/` can't be division
+const REGEX_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "case",
+    "void",
+    "delete",
+    "new",
+    "yield",
+    "await",
+    "throw",
+];
+
+/// Length of the regex literal starting at `i`, if one does. A `/` starts one
+/// where a value is expected, after an operator, `(` or keyword, rather than
+/// after a value, where it divides.
+fn regex_len(content: &str, i: usize) -> Option<usize> {
+    let body = content[i..].strip_prefix('/')?;
+    if body.starts_with(['/', '*']) {
+        return None;
+    }
+
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let before = content[..i].trim_end();
+    let expects_value = match before.chars().next_back() {
+        None => true,
+        Some(c) if is_word(c) => {
+            let word = &before[before.trim_end_matches(is_word).len()..];
+            REGEX_KEYWORDS.contains(&word)
+        }
+        // `<` closes JSX tags, `</div>` is no regex
+        Some(c) => !matches!(c, ')' | ']' | '}' | '"' | '\'' | '`' | '<'),
+    };
+    if !expects_value {
+        return None;
+    }
+
+    // A `/` inside a character class doesn't end the pattern
+    let mut in_class = false;
+    let mut chars = body.char_indices();
+    while let Some((j, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '\n' => return None,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => {
+                let flags = &body[j + 1..];
+                let flags_len = flags
+                    .find(|c: char| !c.is_ascii_alphabetic())
+                    .unwrap_or(flags.len());
+                return Some(j + 2 + flags_len);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Removes comments, along with lines left empty by removing them
+fn strip_comments(content: &str, syntax: &Syntax) -> Vec<Edit> {
+    // Line comments take the whitespace before them along, even past a
+    // block comment on the same line
+    let mut comments: Vec<Range<usize>> = Vec::new();
+    for (range, kind) in tokenize(content, syntax) {
+        let mut start = range.start;
+        match kind {
+            SpanKind::Literal => continue,
+            SpanKind::BlockComment => {}
+            SpanKind::LineComment => loop {
+                start = content[..start].trim_end_matches([' ', '\t']).len();
+                match comments.last() {
+                    Some(previous) if previous.end == start => {
+                        start = previous.start;
+                        comments.pop();
+                    }
+                    _ => break,
+                }
+            },
+        }
+        comments.push(start..range.end);
+    }
+
+    // Lines are split at the newlines left over, a block comment can join two
+    let mut edits = Vec::new();
+    let mut on_line: Vec<Range<usize>> = Vec::new();
+    let mut line_start = 0;
+    let mut blank = true;
+    let mut last = 0;
+    let mut finish_line = |on_line: &mut Vec<Range<usize>>, blank: bool, end: usize| {
+        if blank && !on_line.is_empty() {
+            edits.push((line_start, end, String::new()));
+        } else {
+            edits.extend(on_line.iter().map(|c| (c.start, c.end, String::new())));
+        }
+        on_line.clear();
+        line_start = end;
+    };
+    for comment in comments
+        .into_iter()
+        .chain(std::iter::once(content.len()..content.len()))
+    {
+        for (i, c) in content[last..comment.start].char_indices() {
+            if c == '\n' {
+                finish_line(&mut on_line, blank, last + i + 1);
+                blank = true;
+            } else if !c.is_whitespace() {
+                blank = false;
+            }
+        }
+        if !comment.is_empty() {
+            on_line.push(comment.clone());
+        }
+        last = comment.end;
+    }
+    finish_line(&mut on_line, blank, content.len());
+
+    edits
+}
+
+/// Removes a leading comment that mentions a copyright or license, after
+/// any shebang line
+fn strip_license_header(content: &str, syntax: &Syntax) -> Vec<Edit> {
+    let spans = tokenize(content, syntax);
+    let start = if content.starts_with("#!") {
+        content.find('\n').map_or(content.len(), |i| i + 1)
+    } else {
+        0
+    };
+
+    // The header is the first run of comments, up to a blank line or code
+    let mut header: Option<Range<usize>> = None;
+    for (range, kind) in &spans {
+        if *kind == SpanKind::Literal {
+            break;
+        }
+        let gap = &content[header.as_ref().map_or(start, |h| h.end)..range.start];
+        if !gap.trim().is_empty() || (header.is_some() && gap.matches('\n').count() > 1) {
+            break;
+        }
+        header = Some(header.map_or(range.start, |h| h.start)..range.end);
+    }
+
+    let Some(header) = header else {
+        return Vec::new();
+    };
+    let text = content[header.clone()].to_lowercase();
+    let is_license = ["copyright", "license", "licence", "spdx-license-identifier"]
+        .iter()
+        .any(|marker| text.contains(marker));
+    if !is_license {
+        return Vec::new();
+    }
+
+    // Take the blank lines after the header with it
+    let rest = &content[header.end..];
+    let mut end = header.end;
+    for line in rest.split_inclusive('\n') {
+        if !line.trim().is_empty() {
+            break;
+        }
+        end += line.len();
+    }
+
+    let start = content[..header.start].trim_end_matches([' ', '\t']).len();
+    vec![(start, end, String::new())]
+}
+
+/// Removes blank lines outside of string literals. Prose keeps one blank
+/// line between paragraphs, since that's what separates them.
+fn strip_blank_lines(content: &str, syntax: Option<&Syntax>, prose: bool) -> Vec<Edit> {
+    let literals: Vec<Range<usize>> = syntax
+        .map(|syntax| tokenize(content, syntax))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, kind)| *kind == SpanKind::Literal)
+        .map(|(range, _)| range)
+        .collect();
+    let in_literal = |offset: usize| {
+        literals
+            .iter()
+            .any(|range| range.start < offset && offset < range.end)
+    };
+
+    let mut edits = Vec::new();
+    let mut offset = 0;
+    let mut previous_blank = true;
+    for line in content.split_inclusive('\n') {
+        let blank = line.trim().is_empty() && !in_literal(offset);
+        if blank && (!prose || previous_blank) {
+            edits.push((offset, offset + line.len(), String::new()));
+        }
+        previous_blank = blank;
+        offset += line.len();
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{number_lines, splice, LineOrigin};
+    use crate::source_detection::{self, CustomTypes};
+    use std::path::Path;
+
+    fn strip_file(file: &str, content: &str, kind: StripKind) -> String {
+        let language =
+            source_detection::detect_language_in(Path::new(file), content, &CustomTypes::default());
+        splice(content, &strip(content, language, kind)).0
+    }
+
+    #[test]
+    fn test_strip_comments_keeps_literals() {
+        let content = r##"// Entry point
+fn main<'a>(x: &'a str) {
+    let url = "https://example.com"; // trailing
+    let raw = r#"not // a comment"#;
+    let c = '"'; /* inline */ let d = '/';
+    /*
+     * Block
+     */
+    println!("{}", url);
+}
+"##;
+        let expected = r##"fn main<'a>(x: &'a str) {
+    let url = "https://example.com";
+    let raw = r#"not // a comment"#;
+    let c = '"';  let d = '/';
+    println!("{}", url);
+}
+"##;
+        assert_eq!(
+            strip_file("main.rs", content, StripKind::Comments),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_strip_comments_in_hash_languages() {
+        let content = "#!/usr/bin/env python3\n# comment\nx = \"# not a comment\"  # comment\ns = '''\n# kept\n'''\n";
+        let expected = "#!/usr/bin/env python3\nx = \"# not a comment\"\ns = '''\n# kept\n'''\n";
+        assert_eq!(
+            strip_file("tool.py", content, StripKind::Comments),
+            expected
+        );
+
+        let content = "echo $# ${#list} # count\n";
+        assert_eq!(
+            strip_file("count.sh", content, StripKind::Comments),
+            "echo $# ${#list}\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_comments_keeps_multiline_strings() {
+        let content = "# settings\ns = \"\"\"\n# kept\n\"\"\"\nt = '''\n# kept too\n'''\n";
+        assert_eq!(
+            strip_file("config.toml", content, StripKind::Comments),
+            "s = \"\"\"\n# kept\n\"\"\"\nt = '''\n# kept too\n'''\n"
+        );
+
+        let content = "msg=\"first line\n# not a comment\n\" # comment\n";
+        assert_eq!(
+            strip_file("run.sh", content, StripKind::Comments),
+            "msg=\"first line\n# not a comment\n\"\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_comments_keeps_regex_literals() {
+        let content = "const url = /https?:\\/\\//; // scheme\nif (/[/]\\//g.test(x)) { return a / b / c; } // math\n";
+        let expected =
+            "const url = /https?:\\/\\//;\nif (/[/]\\//g.test(x)) { return a / b / c; }\n";
+        assert_eq!(strip_file("url.js", content, StripKind::Comments), expected);
+    }
+
+    #[test]
+    fn test_strip_license_header() {
+        let content = "// Copyright 2024 Example Corp.\n// SPDX-License-Identifier: MIT\n\n// Parses things\nfn parse() {}\n";
+        assert_eq!(
+            strip_file("parse.rs", content, StripKind::LicenseHeaders),
+            "// Parses things\nfn parse() {}\n"
+        );
+
+        let content = "#!/bin/sh\n# Licensed under the Apache License, Version 2.0\n\necho hi\n";
+        assert_eq!(
+            strip_file("run.sh", content, StripKind::LicenseHeaders),
+            "#!/bin/sh\necho hi\n"
+        );
+
+        let content = "/*\n * Copyright (c) Example\n */\npackage main\n";
+        assert_eq!(
+            strip_file("main.go", content, StripKind::LicenseHeaders),
+            "package main\n"
+        );
+
+        // An ordinary leading comment stays
+        let content = "// Helpers for parsing\nfn parse() {}\n";
+        assert_eq!(
+            strip_file("parse.rs", content, StripKind::LicenseHeaders),
+            content
+        );
+    }
+
+    #[test]
+    fn test_strip_blank_lines() {
+        let content = "fn a() {}\n\n\nfn b() {\n    let s = \"x\n\ny\";\n}\n";
+        assert_eq!(
+            strip_file("lib.rs", content, StripKind::BlankLines),
+            "fn a() {}\nfn b() {\n    let s = \"x\n\ny\";\n}\n"
+        );
+
+        let content = "# Title\n\n\n\nFirst paragraph.\n\nSecond.\n";
+        assert_eq!(
+            strip_file("README.md", content, StripKind::BlankLines),
+            "# Title\n\nFirst paragraph.\n\nSecond.\n"
+        );
+    }
+
+    #[test]
+    fn test_stripped_lines_keep_their_numbers() {
+        let content = "\
+// Copyright 2024 Example
+// SPDX-License-Identifier: MIT
+
+use std::io;
+
+// Reads input
+fn main() {
+    let x = 1; // one
+
+    println!(\"{}\", x);
+}
+";
+        let mut entries = [FileEntry {
+            path: "src/main.rs".into(),
+            content: content.to_string(),
+            size: content.len() as u64,
+            diff: None,
+            language: source_detection::detect_language_in(
+                Path::new("src/main.rs"),
+                content,
+                &CustomTypes::default(),
+            ),
+            origin: LineOrigin::Unchanged,
+        }];
+        let kinds = [
+            StripKind::Comments,
+            StripKind::LicenseHeaders,
+            StripKind::BlankLines,
+        ];
+        strip_entries(&mut entries, &kinds, None).unwrap();
+
+        assert_eq!(
+            number_lines(&entries[0].content, &entries[0].origin),
+            " 4 | use std::io;\n 7 | fn main() {\n 8 |     let x = 1;\n10 |     println!(\"{}\", x);\n11 | }\n"
+        );
+    }
+
+    #[test]
+    fn test_unknown_language_is_untouched() {
+        let content = "// not sure what this is\n";
+        assert_eq!(
+            strip_file("notes.unknown", content, StripKind::Comments),
+            content
+        );
+    }
+}